@@ -0,0 +1,159 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * aggregates over the keys and the shape of a subtree of `Tree`
+ */
+
+use crate::Tree;
+
+/// Keys that can be added while detecting overflows, as used by [`Tree::checked_sum`].
+pub trait CheckedAdd: Sized {
+    /// Returns `self + other`, or `None` if the result does not fit in `Self`.
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_add {
+    ( $( $t:ty ),+ ) => {
+        $(
+            impl CheckedAdd for $t {
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *other)
+                }
+            }
+        )+
+    }
+}
+
+impl_checked_add!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<K> Tree<K> {
+    /// Returns the sum of all the keys in the tree, or `None` if it overflows `K`.
+    /// An empty tree sums to `K::default()`.
+    pub fn checked_sum(&self) -> Option<K>
+    where
+        K: CheckedAdd + Default,
+    {
+        self.fold_subtree(Tree::ROOT_NODE, Some(K::default()), |sum, key| {
+            sum?.checked_add(key)
+        })
+    }
+
+    /// Returns the sum of all the keys in the tree, computed in `u64`.
+    /// The keys have at most 32 bits, so the sum cannot overflow for any tree that fits
+    /// in memory; wider keys are summed with [`Tree::checked_sum`].
+    pub fn sum_u64(&self) -> u64
+    where
+        K: Copy + Into<u32>,
+    {
+        self.fold_subtree(Tree::ROOT_NODE, 0, |sum, key| {
+            sum + u64::from((*key).into())
+        })
+    }
+
+    /// Folds the keys of the subtree rooted at `node_id`, visited in pre-order,
+    /// into a single value starting from `init`.
+    pub fn fold_subtree<B>(&self, node_id: usize, init: B, mut f: impl FnMut(B, &K) -> B) -> B {
+        self.pre_order(node_id)
+            .fold(init, |acc, (_, key)| f(acc, key))
+    }
+
+    /// Returns the smallest key of the subtree rooted at `node_id`,
+    /// or `None` for the root of an empty tree.
+    pub fn min(&self, node_id: usize) -> Option<&K>
+    where
+        K: Ord,
+    {
+        self.pre_order(node_id).map(|(_, key)| key).min()
+    }
+
+    /// Returns the largest key of the subtree rooted at `node_id`,
+    /// or `None` for the root of an empty tree.
+    pub fn max(&self, node_id: usize) -> Option<&K>
+    where
+        K: Ord,
+    {
+        self.pre_order(node_id).map(|(_, key)| key).max()
+    }
+
+    /// Returns the number of keys of the subtree rooted at `node_id` that satisfy `predicate`.
+    pub fn count(&self, node_id: usize, mut predicate: impl FnMut(&K) -> bool) -> usize {
+        self.fold_subtree(node_id, 0, |count, key| count + usize::from(predicate(key)))
+    }
+
+    /// Returns the number of nodes of the subtree rooted at `node_id`.
+    pub fn size(&self, node_id: usize) -> usize {
+        self.pre_order(node_id).count()
+    }
+
+    /// Returns the height of the subtree rooted at `node_id`, i.e. the number of nodes
+    /// on its longest path from the top down to a leaf. An empty tree has height 0.
+    pub fn height(&self, node_id: usize) -> usize {
+        self.evaluate(
+            self.subtree(node_id),
+            || 0,
+            |_, left, right: usize| left.max(right) + 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_sum() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        assert_eq!(tree.checked_sum(), Some(64));
+        assert_eq!(tree.sum_u64(), 64);
+
+        let tree: Tree<u32> = Tree::parse_level_order("[4294967295,1,2]").unwrap();
+        assert_eq!(tree.checked_sum(), None);
+        assert_eq!(tree.sum_u64(), 4294967298);
+
+        let tree: Tree<u64> = Tree::parse_level_order("[18446744073709551615,1]").unwrap();
+        assert_eq!(tree.checked_sum(), None);
+        let tree: Tree<u64> = Tree::parse_level_order("[18446744073709551615,0]").unwrap();
+        assert_eq!(tree.checked_sum(), Some(u64::MAX));
+        let tree: Tree<u16> = Tree::parse_level_order("[65535,65535]").unwrap();
+        assert_eq!(tree.sum_u64(), 131070);
+
+        let tree: Tree<i8> = Tree::parse_level_order("[-100,-28]").unwrap();
+        assert_eq!(tree.checked_sum(), Some(-128));
+        let tree: Tree<i8> = Tree::parse_level_order("[-100,-29]").unwrap();
+        assert_eq!(tree.checked_sum(), None);
+    }
+
+    #[test]
+    fn test_fold_subtree() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+
+        let keys = tree.fold_subtree(Tree::ROOT_NODE, String::new(), |acc, key| {
+            format!("{acc}{key};")
+        });
+        assert_eq!(keys, "10;5;7;22;20;");
+        assert_eq!(tree.fold_subtree(2, 1, |product, key| product * key), 440);
+    }
+
+    #[test]
+    fn test_subtree_aggregates() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+
+        assert_eq!(tree.min(Tree::ROOT_NODE), Some(&5));
+        assert_eq!(tree.max(Tree::ROOT_NODE), Some(&22));
+        assert_eq!(tree.min(2), Some(&20));
+        assert_eq!(tree.count(Tree::ROOT_NODE, |key| key % 2 == 0), 3);
+        assert_eq!(tree.size(Tree::ROOT_NODE), 5);
+        assert_eq!(tree.size(1), 2);
+        assert_eq!(tree.height(Tree::ROOT_NODE), 3);
+        assert_eq!(tree.height(3), 1);
+
+        let mut tree = tree;
+        tree.detach_subtree(Tree::ROOT_NODE).unwrap();
+        assert_eq!(tree.max(Tree::ROOT_NODE), None);
+        assert_eq!(tree.size(Tree::ROOT_NODE), 0);
+        assert_eq!(tree.height(Tree::ROOT_NODE), 0);
+        assert_eq!(tree.checked_sum(), Some(0));
+    }
+}
@@ -0,0 +1,249 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * classic dynamic programming analyses over the subtrees of `Tree`
+ */
+
+use std::ops::Add;
+
+use crate::Tree;
+
+impl<K> Tree<K> {
+    /// Returns the size and the root id of the largest subtree, within the subtree
+    /// rooted at `node_id`, that is a binary search tree, or `None` for the root of an
+    /// empty tree. Among subtrees of the same size, the first one in post-order wins.
+    pub fn largest_bst(&self, node_id: usize) -> Option<(usize, usize)>
+    where
+        K: Ord,
+    {
+        // for every BST subtree: its size, smallest and largest key; `None` otherwise
+        let mut bsts: Vec<Option<(usize, &K, &K)>> = vec![None; self.nodes.len()];
+        let mut largest: Option<(usize, usize)> = None;
+
+        for (id, key) in self.post_order(node_id) {
+            let node = self.get_node(Some(id)).unwrap();
+            let side = |child: Option<usize>| child.map(|child| bsts[child]);
+
+            let (size, min, max) = match (side(node.id_left), side(node.id_right)) {
+                // a subtree that is not a BST makes its ancestors fail as well
+                (Some(None), _) | (_, Some(None)) => continue,
+                (Some(Some((_, _, left_max))), _) if *left_max >= *key => continue,
+                (_, Some(Some((_, right_min, _)))) if *right_min <= *key => continue,
+                (left, right) => {
+                    let (left_size, min) = left.flatten().map_or((0, key), |(s, min, _)| (s, min));
+                    let (right_size, max) =
+                        right.flatten().map_or((0, key), |(s, _, max)| (s, max));
+                    (left_size + right_size + 1, min, max)
+                }
+            };
+
+            bsts[id] = Some((size, min, max));
+            if largest.is_none_or(|(largest_size, _)| size > largest_size) {
+                largest = Some((size, id));
+            }
+        }
+
+        largest
+    }
+
+    /// Returns the largest sum of the keys on a path from `node_id` down to a leaf,
+    /// or `None` for the root of an empty tree.
+    pub fn max_root_to_leaf_sum(&self, node_id: usize) -> Option<K>
+    where
+        K: Clone + Add<Output = K> + Ord,
+    {
+        self.evaluate(
+            self.subtree(node_id),
+            || None,
+            |node, left: Option<K>, right: Option<K>| {
+                // a missing child is not a leaf, so it never ends a path
+                let below = match (left, right) {
+                    (Some(left), Some(right)) => Some(left.max(right)),
+                    (below, None) | (None, below) => below,
+                };
+                Some(match below {
+                    Some(below) => node.key.clone() + below,
+                    None => node.key.clone(),
+                })
+            },
+        )
+    }
+
+    /// Returns the largest sum of the keys on any path between two nodes of the subtree
+    /// rooted at `node_id`, both included, or `None` for the root of an empty tree.
+    /// A path has at least one node and `K::default()` acts as zero.
+    pub fn max_path_sum(&self, node_id: usize) -> Option<K>
+    where
+        K: Clone + Add<Output = K> + Ord + Default,
+    {
+        // the result of every subtree:
+        //  - the largest sum of a path from its root down, or zero if all are negative
+        //  - the largest sum of any path inside it
+        let (_, best) = self.evaluate(
+            self.subtree(node_id),
+            || (K::default(), None),
+            |node, (left_down, left_best), (right_down, right_best): (K, Option<K>)| {
+                let through = node.key.clone() + left_down.clone() + right_down.clone();
+                let down = (node.key.clone() + left_down.max(right_down)).max(K::default());
+                let best = [left_best, right_best]
+                    .into_iter()
+                    .flatten()
+                    .fold(through, K::max);
+
+                (down, Some(best))
+            },
+        );
+
+        best
+    }
+
+    /// Returns the diameter of the subtree rooted at `node_id`, i.e. the number of
+    /// edges on the longest path between two of its nodes. An empty tree has diameter 0.
+    pub fn diameter(&self, node_id: usize) -> usize {
+        // the result of every subtree: its height and its diameter
+        let (_, diameter) = self.evaluate(
+            self.subtree(node_id),
+            || (0, 0),
+            |_, (left_height, left_diameter), (right_height, right_diameter): (usize, usize)| {
+                let diameter = (left_height + right_height)
+                    .max(left_diameter)
+                    .max(right_diameter);
+
+                (left_height.max(right_height) + 1, diameter)
+            },
+        );
+
+        diameter
+    }
+
+    /// Returns the level with the most nodes in the subtree rooted at `node_id`
+    /// (`node_id` is on level 0) together with its number of nodes, or `None` for the
+    /// root of an empty tree. Among levels of the same width, the upper one wins.
+    pub fn widest_level(&self, node_id: usize) -> Option<(usize, usize)> {
+        let mut level: Vec<usize> = self.subtree(node_id).into_iter().collect();
+        let mut widest: Option<(usize, usize)> = None;
+
+        for depth in 0.. {
+            if level.is_empty() {
+                break;
+            }
+            if widest.is_none_or(|(_, width)| level.len() > width) {
+                widest = Some((depth, level.len()));
+            }
+
+            level = level
+                .iter()
+                .flat_map(|&id| {
+                    let node = self.get_node(Some(id)).unwrap();
+                    [node.id_left, node.id_right]
+                })
+                .flatten()
+                .collect();
+        }
+
+        widest
+    }
+
+    /// Returns the number of subtrees, within the subtree rooted at `node_id`,
+    /// whose keys add up to `target`.
+    pub fn count_subtrees_with_sum(&self, node_id: usize, target: &K) -> usize
+    where
+        K: Clone + Add<Output = K> + Default + PartialEq,
+    {
+        // the result of every subtree: its sum and the number of matching subtrees
+        let (_, count) = self.evaluate(
+            self.subtree(node_id),
+            || (K::default(), 0),
+            |node, (left_sum, left_count), (right_sum, right_count): (K, usize)| {
+                let sum = left_sum + right_sum + node.key.clone();
+                let count = left_count + right_count + usize::from(sum == *target);
+
+                (sum, count)
+            },
+        );
+
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_largest_bst() {
+        //        10
+        //     5     15
+        //   1   8      7
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,15,1,8,null,7]").unwrap();
+        assert_eq!(tree.largest_bst(Tree::ROOT_NODE), Some((3, 1)));
+        assert_eq!(tree.largest_bst(2), Some((1, 5)));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,15,1,8,12,20]").unwrap();
+        assert_eq!(tree.largest_bst(Tree::ROOT_NODE), Some((7, 0)));
+
+        // a BST deep inside a subtree that is not a BST
+        let tree: Tree<u32> = Tree::parse_level_order("[1,null,9,null,5,3,7,2,4]").unwrap();
+        assert_eq!(tree.largest_bst(Tree::ROOT_NODE), Some((5, 2)));
+
+        let mut tree = Tree::with_root(1);
+        tree.remove_leaf(Tree::ROOT_NODE).unwrap();
+        assert_eq!(tree.largest_bst(Tree::ROOT_NODE), None);
+    }
+
+    #[test]
+    fn test_path_sums() {
+        //       -10
+        //     9     20
+        //         15   7
+        let tree: Tree<i32> = Tree::parse_level_order("[-10,9,20,null,null,15,7]").unwrap();
+        assert_eq!(tree.max_root_to_leaf_sum(Tree::ROOT_NODE), Some(25));
+        assert_eq!(tree.max_path_sum(Tree::ROOT_NODE), Some(42));
+        assert_eq!(tree.max_path_sum(1), Some(9));
+
+        // a missing child does not end a path
+        let tree: Tree<i32> = Tree::parse_level_order("[5,-8,null,1]").unwrap();
+        assert_eq!(tree.max_root_to_leaf_sum(Tree::ROOT_NODE), Some(-2));
+        assert_eq!(tree.max_path_sum(Tree::ROOT_NODE), Some(5));
+
+        let tree: Tree<i32> = Tree::parse_level_order("[-3,-2,-1]").unwrap();
+        assert_eq!(tree.max_root_to_leaf_sum(Tree::ROOT_NODE), Some(-4));
+        assert_eq!(tree.max_path_sum(Tree::ROOT_NODE), Some(-1));
+    }
+
+    #[test]
+    fn test_diameter() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,5]").unwrap();
+        assert_eq!(tree.diameter(Tree::ROOT_NODE), 3);
+        assert_eq!(tree.diameter(2), 0);
+
+        // the longest path does not cross the root
+        let tree: Tree<u32> =
+            Tree::parse_level_order("[1,2,null,3,4,5,null,null,6,7,null,null,8]").unwrap();
+        assert_eq!(tree.diameter(Tree::ROOT_NODE), 6);
+    }
+
+    #[test]
+    fn test_widest_level() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,null,null,5,6]").unwrap();
+        assert_eq!(tree.widest_level(Tree::ROOT_NODE), Some((1, 2)));
+        assert_eq!(tree.widest_level(1), Some((0, 1)));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,null,5,6]").unwrap();
+        assert_eq!(tree.widest_level(Tree::ROOT_NODE), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_count_subtrees_with_sum() {
+        //        5
+        //     3     -3
+        //   1   2  3   0
+        let tree: Tree<i32> = Tree::parse_level_order("[5,3,-3,1,2,3,0]").unwrap();
+        assert_eq!(tree.count_subtrees_with_sum(Tree::ROOT_NODE, &0), 2);
+        assert_eq!(tree.count_subtrees_with_sum(Tree::ROOT_NODE, &3), 1);
+        assert_eq!(tree.count_subtrees_with_sum(Tree::ROOT_NODE, &6), 1);
+        assert_eq!(tree.count_subtrees_with_sum(2, &6), 0);
+    }
+}
@@ -0,0 +1,285 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * self-balancing AVL tree on top of `Tree`
+ */
+
+use crate::{Bst, Tree};
+
+/// An AVL tree stored in a `Tree`: a binary search tree that rotates after every
+/// insertion and removal so that the heights of the two subtrees of any node differ
+/// by at most one. Both `is_bst` and `is_balanced` hold at any time, and every
+/// operation takes O(log n) in the worst case.
+///
+/// Rotations move keys between nodes instead of relinking them, so the root keeps
+/// the id `ROOT_NODE`, but the id holding a given key may change after an update.
+#[derive(Debug, Clone)]
+pub struct Avl<K> {
+    bst: Bst<K>,
+    // height of the subtree rooted at every id, an empty subtree has height 0
+    heights: Vec<usize>,
+}
+
+impl<K: Ord> Default for Avl<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> Avl<K> {
+    /// Creates an empty AVL tree.
+    pub fn new() -> Self {
+        Self {
+            bst: Bst::new(),
+            heights: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &Tree<K> {
+        self.bst.tree()
+    }
+
+    /// Unwraps the underlying tree.
+    pub fn into_tree(self) -> Tree<K> {
+        self.bst.into_tree()
+    }
+
+    /// Returns the number of keys in the tree.
+    pub fn len(&self) -> usize {
+        self.bst.len()
+    }
+
+    /// Returns `true` if the tree holds no key.
+    pub fn is_empty(&self) -> bool {
+        self.bst.is_empty()
+    }
+
+    /// Returns the height of the whole tree, `0` if it is empty.
+    pub fn height(&self) -> usize {
+        self.height_of(self.tree().subtree(Tree::ROOT_NODE))
+    }
+
+    /// Inserts `key`. Returns `false` if the key was already present,
+    /// in which case the tree is unchanged.
+    pub fn insert(&mut self, key: K) -> bool {
+        let mut path = Vec::new();
+        let Some(leaf) = self.bst.insert_by(key, |node_id| path.push(node_id)) else {
+            return false;
+        };
+
+        if self.heights.len() <= leaf {
+            self.heights.resize(leaf + 1, 0);
+        }
+        self.heights[leaf] = 1;
+        self.rebalance_path(path);
+
+        true
+    }
+
+    /// Removes `key`. Returns `false` if the key was not present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let mut path = Vec::new();
+        if !self.bst.remove_by(key, |node_id| path.push(node_id)) {
+            return false;
+        }
+
+        self.rebalance_path(path);
+
+        true
+    }
+
+    /// Returns `true` if the tree holds `key`.
+    pub fn contains(&self, key: &K) -> bool {
+        self.bst.contains(key)
+    }
+
+    /// Returns the id of the node holding `key`.
+    pub fn find(&self, key: &K) -> Option<usize> {
+        self.bst.find(key)
+    }
+
+    /// Returns the smallest key of the tree.
+    pub fn min(&self) -> Option<&K> {
+        self.bst.min()
+    }
+
+    /// Returns the largest key of the tree.
+    pub fn max(&self) -> Option<&K> {
+        self.bst.max()
+    }
+
+    /// Returns the smallest key of the tree that is greater than `key`.
+    pub fn successor(&self, key: &K) -> Option<&K> {
+        self.bst.successor(key)
+    }
+
+    /// Returns the largest key of the tree that is smaller than `key`.
+    pub fn predecessor(&self, key: &K) -> Option<&K> {
+        self.bst.predecessor(key)
+    }
+
+    fn height_of(&self, node_id: Option<usize>) -> usize {
+        node_id.map_or(0, |id| self.heights[id])
+    }
+
+    // Recomputes the height of `node_id` from the heights of its children
+    fn update_height(&mut self, node_id: usize) {
+        let node = self.tree().get_node(Some(node_id)).unwrap();
+        self.heights[node_id] = self
+            .height_of(node.id_left)
+            .max(self.height_of(node.id_right))
+            + 1;
+    }
+
+    // Walks the ancestors of a modified node from the bottom up, fixing their heights
+    // and rotating where the AVL condition is broken. The rotations keep every node of
+    // the path at the top of its subtree, so the path stays valid along the way.
+    fn rebalance_path(&mut self, path: Vec<usize>) {
+        for node_id in path.into_iter().rev() {
+            self.update_height(node_id);
+            self.rebalance(node_id);
+        }
+    }
+
+    fn rebalance(&mut self, node_id: usize) {
+        let node = self.tree().get_node(Some(node_id)).unwrap();
+        let (left, right) = (node.id_left, node.id_right);
+        let (left_height, right_height) = (self.height_of(left), self.height_of(right));
+
+        if left_height > right_height + 1 {
+            let left = left.unwrap();
+            let child = self.tree().get_node(Some(left)).unwrap();
+            // left-right case: straighten the left subtree first
+            if self.height_of(child.id_left) < self.height_of(child.id_right) {
+                self.rotate(left, false);
+            }
+            self.rotate(node_id, true);
+        } else if right_height > left_height + 1 {
+            let right = right.unwrap();
+            let child = self.tree().get_node(Some(right)).unwrap();
+            // right-left case: straighten the right subtree first
+            if self.height_of(child.id_right) < self.height_of(child.id_left) {
+                self.rotate(right, true);
+            }
+            self.rotate(node_id, false);
+        }
+    }
+
+    // Rotates right (lifting the left child) if `right` is `true`, left otherwise,
+    // and fixes the heights of the two nodes involved. The lifted child always exists.
+    fn rotate(&mut self, node_id: usize, right: bool) {
+        let moved_down = if right {
+            self.bst.tree.rotate_right(node_id)
+        } else {
+            self.bst.tree.rotate_left(node_id)
+        }
+        .unwrap();
+
+        self.update_height(moved_down);
+        self.update_height(node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    fn assert_valid(avl: &Avl<u32>) {
+        assert!(avl.tree().is_bst(Tree::ROOT_NODE));
+        assert!(avl.tree().is_balanced(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_sorted_insertions() {
+        // inserting sorted keys is the worst case of a plain BST
+        let mut avl = Avl::new();
+        for key in 0..1023 {
+            assert!(avl.insert(key));
+            assert_valid(&avl);
+        }
+        assert!(!avl.insert(5));
+
+        assert_eq!(avl.len(), 1023);
+        assert_eq!(avl.height(), 10);
+        assert_eq!(avl.min(), Some(&0));
+        assert_eq!(avl.max(), Some(&1022));
+        assert_eq!(avl.successor(&511), Some(&512));
+    }
+
+    #[test]
+    fn test_double_rotations() {
+        let mut avl = Avl::new();
+        for key in [30, 10, 20] {
+            avl.insert(key);
+        }
+        // left-right case
+        assert_eq!(*avl.tree().get(Tree::ROOT_NODE), 20);
+        assert_valid(&avl);
+
+        let mut avl = Avl::new();
+        for key in [10, 30, 20] {
+            avl.insert(key);
+        }
+        // right-left case
+        assert_eq!(*avl.tree().get(Tree::ROOT_NODE), 20);
+        assert_valid(&avl);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut avl = Avl::new();
+        for key in 0..100 {
+            avl.insert(key);
+        }
+
+        // removing the whole left part forces rotations at the root
+        for key in 0..60 {
+            assert!(avl.remove(&key));
+            assert_valid(&avl);
+        }
+        assert!(!avl.remove(&0));
+        assert_eq!(avl.len(), 40);
+        assert_eq!(avl.min(), Some(&60));
+
+        for key in 60..100 {
+            avl.remove(&key);
+            assert_valid(&avl);
+        }
+        assert!(avl.is_empty());
+        assert_eq!(avl.height(), 0);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng = Rng::new(7);
+        let mut next = || rng.below(512) as u32;
+
+        let mut avl = Avl::new();
+        let mut expected = std::collections::BTreeSet::new();
+        for _ in 0..5000 {
+            let key = next();
+            if next() % 2 == 0 {
+                assert_eq!(avl.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(avl.insert(key), expected.insert(key));
+            }
+
+            assert_valid(&avl);
+            assert_eq!(avl.len(), expected.len());
+            // the AVL height bound, 1.44 * log2(n + 2)
+            let bound = 1.45 * ((avl.len() + 2) as f64).log2();
+            assert!(avl.height() as f64 <= bound);
+        }
+
+        let keys: Vec<u32> = avl
+            .tree()
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .collect();
+        assert_eq!(keys, expected.into_iter().collect::<Vec<_>>());
+    }
+}
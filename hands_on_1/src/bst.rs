@@ -0,0 +1,402 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * binary search tree operations on top of `Tree`
+ */
+
+use std::cmp::Ordering;
+
+use crate::Tree;
+
+/// A binary search tree stored in a `Tree`.
+/// Every operation keeps the BST property, so `tree().is_bst(Tree::ROOT_NODE)` holds
+/// at any time. Keys are unique; the tree may be empty.
+///
+/// The root always keeps the id `ROOT_NODE`: removing a node may move the key of
+/// another node into its slot, so the ids of the keys are not stable across removals.
+#[derive(Debug, Clone)]
+pub struct Bst<K> {
+    pub(crate) tree: Tree<K>,
+}
+
+impl<K: Ord> Default for Bst<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> Bst<K> {
+    /// Creates an empty binary search tree.
+    pub fn new() -> Self {
+        Self {
+            tree: Tree::empty(),
+        }
+    }
+
+    /// Wraps a tree that already is a binary search tree.
+    /// The tree is handed back if it does not satisfy the BST property.
+    pub fn from_tree(tree: Tree<K>) -> Result<Self, Tree<K>> {
+        if tree.is_bst(Tree::ROOT_NODE) {
+            Ok(Self { tree })
+        } else {
+            Err(tree)
+        }
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &Tree<K> {
+        &self.tree
+    }
+
+    /// Unwraps the underlying tree.
+    pub fn into_tree(self) -> Tree<K> {
+        self.tree
+    }
+
+    /// Returns the number of keys in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if the tree holds no key.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Inserts `key` as a new leaf. Returns `false` if the key was already present,
+    /// in which case the tree is unchanged.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.insert_by(key, |_| ()).is_some()
+    }
+
+    // Inserts `key` and returns the id of the new leaf, or `None` if the key was present.
+    // `on_descend` is called with every ancestor of the new leaf, from the root down.
+    pub(crate) fn insert_by(&mut self, key: K, mut on_descend: impl FnMut(usize)) -> Option<usize> {
+        if self.tree.is_empty() {
+            return Some(self.tree.allocate(key));
+        }
+
+        let mut node_id = Tree::ROOT_NODE;
+        loop {
+            let node = self.tree.get_node(Some(node_id)).unwrap();
+            let (child, is_left) = match key.cmp(&node.key) {
+                Ordering::Less => (node.id_left, true),
+                Ordering::Greater => (node.id_right, false),
+                Ordering::Equal => return None,
+            };
+
+            on_descend(node_id);
+            match child {
+                Some(child) => node_id = child,
+                None => return Some(self.tree.add_node(node_id, key, is_left)),
+            }
+        }
+    }
+
+    /// Returns `true` if the tree holds `key`.
+    pub fn contains(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Returns the id of the node holding `key`.
+    pub fn find(&self, key: &K) -> Option<usize> {
+        self.search_by(key, |_| ()).0
+    }
+
+    /// Removes `key` from the tree. Returns `false` if the key was not present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.remove_by(key, |_| ())
+    }
+
+    // Removes `key` like `remove`. `on_descend` is called, from the root down, with every
+    // node whose subtree loses a node: the ancestors of the node that is actually taken
+    // out of the tree, or the root when its only child moves into the root's slot.
+    pub(crate) fn remove_by(&mut self, key: &K, mut on_descend: impl FnMut(usize)) -> bool {
+        let (node_id, mut parent) = match self.search_by(key, &mut on_descend) {
+            (Some(node_id), parent) => (node_id, parent),
+            (None, _) => return false,
+        };
+
+        // a node with two children takes the key of its successor,
+        // which is then removed from the right subtree instead
+        let node = self.tree.get_node(Some(node_id)).unwrap();
+        let mut target = node_id;
+        if let (Some(_), Some(right)) = (node.id_left, node.id_right) {
+            on_descend(node_id);
+            parent = Some((node_id, false));
+            target = right;
+            while let Some(left) = self.tree.get_node(Some(target)).unwrap().id_left {
+                on_descend(target);
+                parent = Some((target, true));
+                target = left;
+            }
+            self.tree.swap_keys(node_id, target);
+        }
+
+        self.splice(target, parent, on_descend);
+        true
+    }
+
+    /// Returns the smallest key of the tree.
+    pub fn min(&self) -> Option<&K> {
+        self.extreme(true)
+    }
+
+    /// Returns the largest key of the tree.
+    pub fn max(&self) -> Option<&K> {
+        self.extreme(false)
+    }
+
+    /// Returns the smallest key of the tree that is greater than `key`.
+    /// `key` itself does not need to be in the tree.
+    pub fn successor(&self, key: &K) -> Option<&K> {
+        self.neighbour(key, Ordering::Greater)
+    }
+
+    /// Returns the largest key of the tree that is smaller than `key`.
+    /// `key` itself does not need to be in the tree.
+    pub fn predecessor(&self, key: &K) -> Option<&K> {
+        self.neighbour(key, Ordering::Less)
+    }
+
+    // Descends from the root towards `key`. Returns the node holding `key`, if any,
+    // and the parent of the last node visited together with the side of the link.
+    // `on_descend` is called with every node the search moves down from.
+    fn search_by(
+        &self,
+        key: &K,
+        mut on_descend: impl FnMut(usize),
+    ) -> (Option<usize>, Option<(usize, bool)>) {
+        let mut node_id = self.tree.subtree(Tree::ROOT_NODE);
+        let mut parent = None;
+
+        while let Some(node) = self.tree.get_node(node_id) {
+            let id = node_id.unwrap();
+            let is_left = match key.cmp(&node.key) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => return (Some(id), parent),
+            };
+
+            on_descend(id);
+            parent = Some((id, is_left));
+            node_id = if is_left { node.id_left } else { node.id_right };
+        }
+
+        (None, parent)
+    }
+
+    // Removes a node with at most one child, linking the child to the node's parent.
+    // `on_descend` is called with the root if it takes over the slot of its child.
+    fn splice(
+        &mut self,
+        node_id: usize,
+        parent: Option<(usize, bool)>,
+        mut on_descend: impl FnMut(usize),
+    ) {
+        let node = self.tree.get_node(Some(node_id)).unwrap();
+        let child = node.id_left.or(node.id_right);
+
+        match (parent, child) {
+            (Some((parent_id, is_left)), _) => {
+                self.tree.set_child(parent_id, is_left, child);
+                self.tree.release(node_id);
+            }
+            // the root must keep its id, so its only child moves into the root's slot
+            (None, Some(child)) => {
+                on_descend(node_id);
+                let mut child = self.tree.release(child);
+                child.id_parent = None;
+                let grandchildren = [child.id_left, child.id_right];
+                *self.tree.get_node_mut(node_id) = child;
+                for grandchild in grandchildren.into_iter().flatten() {
+                    self.tree.get_node_mut(grandchild).id_parent = Some(node_id);
+                }
+            }
+            (None, None) => {
+                self.tree.release(node_id);
+            }
+        }
+    }
+
+    // The leftmost (if `left` is `true`) or the rightmost key
+    fn extreme(&self, left: bool) -> Option<&K> {
+        let mut node = self.tree.get_node(self.tree.subtree(Tree::ROOT_NODE))?;
+        while let Some(child) = if left { node.id_left } else { node.id_right } {
+            node = self.tree.get_node(Some(child)).unwrap();
+        }
+
+        Some(&node.key)
+    }
+
+    // The closest key on the `side` of `key`: following the path to `key`, the best
+    // candidate is the last node where the path turned away from `side`.
+    fn neighbour(&self, key: &K, side: Ordering) -> Option<&K> {
+        let mut node_id = self.tree.subtree(Tree::ROOT_NODE);
+        let mut candidate = None;
+
+        while let Some(node) = self.tree.get_node(node_id) {
+            if node.key.cmp(key) == side {
+                candidate = Some(&node.key);
+                node_id = if side == Ordering::Greater {
+                    node.id_left
+                } else {
+                    node.id_right
+                };
+            } else if side == Ordering::Greater {
+                node_id = node.id_right;
+            } else {
+                node_id = node.id_left;
+            }
+        }
+
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    fn assert_valid(bst: &Bst<u32>) {
+        assert!(bst.tree().is_bst(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_insert_contains() {
+        let mut bst = Bst::new();
+        assert!(!bst.contains(&10));
+        assert_eq!(bst.min(), None);
+
+        for key in [10, 5, 22, 7, 20, 1] {
+            assert!(bst.insert(key));
+            assert_valid(&bst);
+        }
+        assert!(!bst.insert(7));
+
+        assert_eq!(bst.len(), 6);
+        assert!(bst.contains(&7));
+        assert!(!bst.contains(&8));
+        assert_eq!(bst.min(), Some(&1));
+        assert_eq!(bst.max(), Some(&22));
+        assert_eq!(bst.find(&10), Some(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_successor_predecessor() {
+        let mut bst = Bst::new();
+        for key in [10, 5, 22, 7, 20, 1] {
+            bst.insert(key);
+        }
+
+        assert_eq!(bst.successor(&7), Some(&10));
+        assert_eq!(bst.successor(&8), Some(&10));
+        assert_eq!(bst.successor(&10), Some(&20));
+        assert_eq!(bst.successor(&0), Some(&1));
+        assert_eq!(bst.successor(&22), None);
+
+        assert_eq!(bst.predecessor(&10), Some(&7));
+        assert_eq!(bst.predecessor(&20), Some(&10));
+        assert_eq!(bst.predecessor(&21), Some(&20));
+        assert_eq!(bst.predecessor(&1), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut bst = Bst::new();
+        for key in [10, 5, 22, 7, 20, 1, 25, 21] {
+            bst.insert(key);
+        }
+
+        // a leaf, a node with one child, a node with two children
+        for key in [1, 20, 10] {
+            assert!(bst.remove(&key));
+            assert!(!bst.contains(&key));
+            assert_valid(&bst);
+        }
+        assert!(!bst.remove(&10));
+
+        let keys: Vec<u32> = bst
+            .tree()
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .collect();
+        assert_eq!(keys, vec![5, 7, 21, 22, 25]);
+
+        // the root with a single child
+        for key in [21, 22, 25] {
+            bst.remove(&key);
+            assert_valid(&bst);
+        }
+        assert_eq!(*bst.tree().get(Tree::ROOT_NODE), 5);
+        assert!(bst.remove(&5));
+        assert_eq!(*bst.tree().get(Tree::ROOT_NODE), 7);
+
+        assert!(bst.remove(&7));
+        assert!(bst.is_empty());
+        assert_valid(&bst);
+
+        assert!(bst.insert(3));
+        assert_eq!(bst.min(), Some(&3));
+    }
+
+    #[test]
+    fn test_remove_by_path() {
+        let mut bst = Bst::new();
+        for key in [10, 5, 22, 7, 20] {
+            bst.insert(key);
+        }
+
+        // the successor 20 is taken out of the right subtree
+        let mut path = Vec::new();
+        assert!(bst.remove_by(&10, |node_id| path.push(node_id)));
+        assert_eq!(path, vec![0, 2]);
+
+        // the root loses its only child, which moves into the root's slot
+        for key in [20, 22] {
+            bst.remove(&key);
+        }
+        let mut path = Vec::new();
+        assert!(bst.remove_by(&5, |node_id| path.push(node_id)));
+        assert_eq!(path, vec![Tree::ROOT_NODE]);
+        assert_eq!(*bst.tree().get(Tree::ROOT_NODE), 7);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng = Rng::new(42);
+        let mut next = || rng.below(64) as u32;
+
+        let mut bst = Bst::new();
+        let mut expected = std::collections::BTreeSet::new();
+        for _ in 0..2000 {
+            let key = next();
+            if next() % 3 == 0 {
+                assert_eq!(bst.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(bst.insert(key), expected.insert(key));
+            }
+
+            assert_valid(&bst);
+            assert_eq!(bst.len(), expected.len());
+            assert_eq!(bst.min(), expected.first());
+            assert_eq!(bst.max(), expected.last());
+            assert_eq!(bst.successor(&key), expected.range(key + 1..).next());
+            assert_eq!(bst.predecessor(&key), expected.range(..key).next_back());
+        }
+    }
+
+    #[test]
+    fn test_from_tree() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        let mut bst = Bst::from_tree(tree).unwrap();
+        bst.insert(6);
+        assert_eq!(bst.predecessor(&7), Some(&6));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[10,22,5]").unwrap();
+        assert!(Bst::from_tree(tree).is_err());
+    }
+}
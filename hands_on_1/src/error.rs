@@ -0,0 +1,127 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * errors reported by the fallible operations on `Tree`
+ */
+
+use std::error::Error;
+use std::fmt;
+
+/// The reasons why an operation on a `Tree` can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeError {
+    /// The parent passed to `try_add_node` does not exist.
+    UnknownParent(usize),
+    /// The requested child of `parent_id` is already set.
+    SlotOccupied { parent_id: usize, is_left: bool },
+    /// The node id does not refer to any node of the tree.
+    IdOutOfRange(usize),
+    /// The node id referred to a node that has been removed.
+    NodeRemoved(usize),
+    /// The operation expects a node without children.
+    NotALeaf(usize),
+    /// The operation expects a complete tree, but this node follows an empty position
+    /// in level-order, or lacks a child that precedes the last node in level-order.
+    NotComplete(usize),
+    /// The node has no child on the requested side.
+    MissingChild { node_id: usize, is_left: bool },
+    /// The first node is an ancestor of the second one, so their subtrees overlap.
+    Overlapping { ancestor_id: usize, node_id: usize },
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::UnknownParent(id) => write!(f, "Parent node id {id} does not exist"),
+            TreeError::SlotOccupied { parent_id, is_left } => write!(
+                f,
+                "Parent node {parent_id} has the {} child already set",
+                if *is_left { "left" } else { "right" }
+            ),
+            TreeError::IdOutOfRange(id) => write!(f, "Node id {id} is out of range"),
+            TreeError::NodeRemoved(id) => write!(f, "Node {id} has been removed"),
+            TreeError::NotALeaf(id) => write!(f, "Node {id} is not a leaf"),
+            TreeError::MissingChild { node_id, is_left } => write!(
+                f,
+                "Node {node_id} has no {} child",
+                if *is_left { "left" } else { "right" }
+            ),
+            TreeError::Overlapping {
+                ancestor_id,
+                node_id,
+            } => write!(
+                f,
+                "Node {ancestor_id} is an ancestor of node {node_id}, their subtrees overlap"
+            ),
+            TreeError::NotComplete(id) => {
+                write!(f, "The tree is not complete at node {id}")
+            }
+        }
+    }
+}
+
+impl Error for TreeError {}
+
+/// The reasons why a description of a tree cannot be turned into a `Tree`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The description has no root, or the root is a null entry.
+    MissingRoot,
+    /// More than one node of a parent array has no parent.
+    MultipleRoots,
+    /// The entry at this position has no node it could be a child of.
+    Orphan(usize),
+    /// The entry at this position is not a valid key.
+    InvalidKey(usize),
+    /// The two sequences describing the tree differ in length, e.g. the keys and the
+    /// parents of a parent array.
+    LengthMismatch,
+    /// The parent of the entry at this position does not exist.
+    UnknownParent(usize),
+    /// The node at this position is listed as parent of more than two nodes.
+    TooManyChildren(usize),
+    /// The node at this position cannot be reached from the root.
+    Unreachable(usize),
+    /// The edge at this position was rejected by `try_add_node`.
+    InvalidEdge(usize, TreeError),
+    /// The key at this position of a traversal already appeared before it.
+    DuplicateKey(usize),
+    /// The traversals do not describe the same tree, or the preorder sequence is not
+    /// the one of a binary search tree.
+    Inconsistent,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingRoot => write!(f, "The tree has no root"),
+            ParseError::MultipleRoots => write!(f, "The tree has more than one root"),
+            ParseError::Orphan(index) => write!(f, "Entry {index} has no parent"),
+            ParseError::InvalidKey(index) => write!(f, "Entry {index} is not a valid key"),
+            ParseError::LengthMismatch => write!(f, "The sequences differ in length"),
+            ParseError::UnknownParent(index) => {
+                write!(f, "The parent of entry {index} does not exist")
+            }
+            ParseError::TooManyChildren(index) => {
+                write!(f, "Entry {index} has more than two children")
+            }
+            ParseError::Unreachable(index) => {
+                write!(f, "Entry {index} is not reachable from the root")
+            }
+            ParseError::InvalidEdge(index, err) => write!(f, "Edge {index} is invalid: {err}"),
+            ParseError::DuplicateKey(index) => write!(f, "Entry {index} repeats an earlier key"),
+            ParseError::Inconsistent => write!(f, "The traversals do not describe a tree"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::InvalidEdge(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,341 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * seeded random generators of trees, for tests and benchmarks
+ */
+
+use crate::Tree;
+
+// A small xorshift generator: the same seed always produces the same sequence
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // xorshift never leaves the all-zero state
+        Self(if seed == 0 { 0x2545f4914f6cdd1d } else { seed })
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+/// Builds random trees of `u32` keys with a given number of nodes.
+/// Two generators created with the same seed produce the same sequence of trees.
+///
+/// Every generator produces a tree satisfying its property when `valid` is `true`,
+/// and a tree of the same size violating it otherwise. Only the stated property is
+/// broken: for example a broken balanced tree is still a binary search tree.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+}
+
+impl Generator {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Inserts random distinct keys one by one into a binary search tree.
+    /// A broken tree is not a BST.
+    ///
+    /// # Panics
+    /// Panics if a broken tree is requested with fewer than 2 nodes.
+    pub fn random_bst(&mut self, size: usize, valid: bool) -> Tree<u32> {
+        let mut bst = crate::Bst::new();
+        while bst.len() < size {
+            bst.insert(self.rng.below(u32::MAX as u64) as u32);
+        }
+
+        let mut tree = bst.into_tree();
+        if !valid {
+            self.break_bst(&mut tree);
+        }
+
+        tree
+    }
+
+    /// Builds a binary search tree in which the sizes of the two subtrees of every node
+    /// differ by at most one, so its height is the smallest possible.
+    /// A broken tree is a BST that is not balanced.
+    ///
+    /// # Panics
+    /// Panics if a broken tree is requested with fewer than 3 nodes.
+    pub fn balanced(&mut self, size: usize, valid: bool) -> Tree<u32> {
+        if valid {
+            let mut tree = Self::balanced_shape(size);
+            self.assign_in_order(&mut tree);
+            return tree;
+        }
+
+        assert!(size >= 3, "A broken balanced tree needs at least 3 nodes");
+        // two more levels below a deepest node unbalance it
+        let mut tree = Self::balanced_shape(size - 2);
+        let (deepest, _) = tree.level_order(Tree::ROOT_NODE).last().unwrap();
+        let child_id = tree.add_node(deepest, 0, true);
+        tree.add_node(child_id, 0, true);
+        self.assign_in_order(&mut tree);
+
+        tree
+    }
+
+    /// Builds a complete max-heap of random keys.
+    /// A broken tree is complete, but one of its nodes is greater than its parent.
+    ///
+    /// # Panics
+    /// Panics if a broken tree is requested with fewer than 2 nodes.
+    pub fn max_heap(&mut self, size: usize, valid: bool) -> Tree<u32> {
+        let mut keys: Vec<u32> = (0..size).map(|_| self.rng.below(1 << 30) as u32).collect();
+
+        // bottom-up heap construction on the implicit layout
+        for start in (0..size / 2).rev() {
+            let mut i = start;
+            loop {
+                let largest = [2 * i + 1, 2 * i + 2]
+                    .into_iter()
+                    .filter(|&child| child < size)
+                    .fold(i, |largest, child| {
+                        if keys[child] > keys[largest] {
+                            child
+                        } else {
+                            largest
+                        }
+                    });
+                if largest == i {
+                    break;
+                }
+                keys.swap(i, largest);
+                i = largest;
+            }
+        }
+
+        if !valid {
+            assert!(size >= 2, "A broken heap needs at least 2 nodes");
+            let i = 1 + self.rng.below(size as u64 - 1) as usize;
+            keys[i] = keys[(i - 1) / 2] + 1;
+        }
+
+        Tree::from_implicit_array(keys)
+    }
+
+    /// Builds a degenerate tree in which every node has at most one child, on a random
+    /// side. The keys increase in in-order, so a valid tree is a binary search tree.
+    /// A broken tree is not a BST.
+    ///
+    /// # Panics
+    /// Panics if a broken tree is requested with fewer than 2 nodes.
+    pub fn chain(&mut self, size: usize, valid: bool) -> Tree<u32> {
+        if size == 0 {
+            return Tree::empty();
+        }
+
+        let mut tree = Tree::with_root(0);
+        let mut last = Tree::ROOT_NODE;
+        for _ in 1..size {
+            last = tree.add_node(last, 0, self.rng.below(2) == 0);
+        }
+        self.assign_in_order(&mut tree);
+        if !valid {
+            self.break_bst(&mut tree);
+        }
+
+        tree
+    }
+
+    /// Builds a random shape, chosen uniformly among all the binary trees with `size`
+    /// nodes. The keys increase in in-order, so a valid tree is a binary search tree.
+    /// A broken tree is not a BST.
+    ///
+    /// # Panics
+    /// Panics if a broken tree is requested with fewer than 2 nodes.
+    pub fn uniform(&mut self, size: usize, valid: bool) -> Tree<u32> {
+        let mut tree = self.uniform_shape(size);
+        self.assign_in_order(&mut tree);
+        if !valid {
+            self.break_bst(&mut tree);
+        }
+
+        tree
+    }
+
+    // The shape of a balanced tree, built top-down by splitting the sizes in halves
+    fn balanced_shape(size: usize) -> Tree<u32> {
+        if size == 0 {
+            return Tree::empty();
+        }
+
+        let mut tree = Tree::with_root(0);
+        // nodes whose subtrees still have to be built, with the size of their subtree
+        let mut stack = vec![(Tree::ROOT_NODE, size)];
+        while let Some((node_id, size)) = stack.pop() {
+            let left_size = (size - 1) / 2;
+            for (is_left, child_size) in [(true, left_size), (false, size - 1 - left_size)] {
+                if child_size > 0 {
+                    stack.push((tree.add_node(node_id, 0, is_left), child_size));
+                }
+            }
+        }
+
+        tree
+    }
+
+    // A uniformly random shape, built with Rémy's algorithm: a full binary tree grows by
+    // replacing a uniformly chosen node with a new internal node, whose children are the
+    // replaced node and a new leaf on a random side. The internal nodes of the final
+    // full tree form a uniformly random binary tree with `size` nodes.
+    fn uniform_shape(&mut self, size: usize) -> Tree<u32> {
+        if size == 0 {
+            return Tree::empty();
+        }
+
+        // the full tree: children of internal nodes, and parent with side of every node
+        let mut children: Vec<Option<[usize; 2]>> = vec![None];
+        let mut parents: Vec<Option<(usize, usize)>> = vec![None];
+        let mut root = 0;
+        for _ in 0..size {
+            let replaced = self.rng.below(children.len() as u64) as usize;
+            let (internal, leaf) = (children.len(), children.len() + 1);
+            let side = self.rng.below(2) as usize;
+            let mut pair = [replaced, leaf];
+            pair.swap(0, side);
+
+            match parents[replaced] {
+                Some((parent, parent_side)) => {
+                    children[parent].as_mut().unwrap()[parent_side] = internal
+                }
+                None => root = internal,
+            }
+            children.push(Some(pair));
+            parents.push(parents[replaced]);
+            children.push(None);
+            parents.push(Some((internal, 1 - side)));
+            parents[replaced] = Some((internal, side));
+        }
+
+        let mut tree = Tree::with_root(0);
+        let mut stack = vec![(root, Tree::ROOT_NODE)];
+        while let Some((internal, node_id)) = stack.pop() {
+            for (side, child) in children[internal].unwrap().into_iter().enumerate() {
+                if children[child].is_some() {
+                    stack.push((child, tree.add_node(node_id, 0, side == 0)));
+                }
+            }
+        }
+
+        tree
+    }
+
+    // Gives the nodes random keys that increase in in-order
+    fn assign_in_order(&mut self, tree: &mut Tree<u32>) {
+        let ids: Vec<usize> = tree.in_order(Tree::ROOT_NODE).map(|(id, _)| id).collect();
+        let mut key = 0;
+        for id in ids {
+            key += 1 + self.rng.below(3) as u32;
+            tree.get_node_mut(id).key = key;
+        }
+    }
+
+    // Exchanges the keys of two random nodes of a tree with distinct keys.
+    // The in-order sequence is no longer increasing, so the tree is no longer a BST.
+    fn break_bst(&mut self, tree: &mut Tree<u32>) {
+        assert!(tree.len() >= 2, "A broken BST needs at least 2 nodes");
+
+        let ids: Vec<usize> = tree.pre_order(Tree::ROOT_NODE).map(|(id, _)| id).collect();
+        let a = self.rng.below(ids.len() as u64) as usize;
+        let b = (a + 1 + self.rng.below(ids.len() as u64 - 1) as usize) % ids.len();
+        tree.swap_keys(ids[a], ids[b]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators() {
+        let mut generator = Generator::new(42);
+
+        for size in [3, 4, 10, 31, 100, 1000] {
+            let tree = generator.random_bst(size, true);
+            assert_eq!(tree.len(), size);
+            assert!(tree.is_bst(Tree::ROOT_NODE));
+            assert!(!generator.random_bst(size, false).is_bst(Tree::ROOT_NODE));
+
+            let tree = generator.balanced(size, true);
+            assert_eq!(tree.len(), size);
+            assert!(tree.is_balanced(Tree::ROOT_NODE));
+            assert_eq!(tree.height(Tree::ROOT_NODE), size.ilog2() as usize + 1);
+            let tree = generator.balanced(size, false);
+            assert_eq!(tree.len(), size);
+            assert!(!tree.is_balanced(Tree::ROOT_NODE));
+            assert!(tree.is_bst(Tree::ROOT_NODE));
+
+            let tree = generator.max_heap(size, true);
+            assert_eq!(tree.len(), size);
+            assert!(tree.is_max_heap(Tree::ROOT_NODE));
+            let tree = generator.max_heap(size, false);
+            assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+            assert!(tree.is_complete(Tree::ROOT_NODE));
+
+            let tree = generator.chain(size, true);
+            assert_eq!(tree.len(), size);
+            assert_eq!(tree.height(Tree::ROOT_NODE), size);
+            assert!(tree.is_bst(Tree::ROOT_NODE));
+            assert!(!generator.chain(size, false).is_bst(Tree::ROOT_NODE));
+
+            let tree = generator.uniform(size, true);
+            tree.assert_links();
+            assert_eq!(tree.len(), size);
+            assert!(tree.is_bst(Tree::ROOT_NODE));
+            assert!(!generator.uniform(size, false).is_bst(Tree::ROOT_NODE));
+        }
+
+        assert!(generator.uniform(0, true).is_empty());
+        assert_eq!(generator.balanced(1, true).len(), 1);
+    }
+
+    #[test]
+    fn test_seeded() {
+        let trees = |seed| {
+            let mut generator = Generator::new(seed);
+            (generator.random_bst(50, true), generator.uniform(50, false))
+        };
+
+        assert_eq!(trees(7), trees(7));
+        assert_ne!(trees(7), trees(8));
+    }
+
+    #[test]
+    fn test_uniform_shapes() {
+        // there are 5 shapes with 3 nodes, each should come up about 1 time out of 5
+        let mut generator = Generator::new(1);
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..5000 {
+            let tree = generator.uniform(3, true);
+            // the depths of the nodes in in-order tell the shapes apart
+            let shape: Vec<usize> = tree
+                .in_order(Tree::ROOT_NODE)
+                .map(|(id, _)| tree.depth(id))
+                .collect();
+            *counts.entry(shape).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), 5);
+        for count in counts.values() {
+            assert!((800..1200).contains(count), "{counts:?}");
+        }
+    }
+}
@@ -0,0 +1,317 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * max-heap operations on the linked representation of `Tree`, and conversions
+ * to and from the implicit array layout of heaps
+ */
+
+use std::collections::VecDeque;
+
+use crate::{Tree, TreeError};
+
+impl<K: PartialOrd> Tree<K> {
+    /// Adds `key` to a tree that is a max-heap and returns the id of the new node.
+    /// The node is appended at the next free position of the last level and its key
+    /// is then sifted up, so `is_max_heap(ROOT_NODE)` still holds afterwards.
+    /// Keys are exchanged between nodes while sifting, so the returned id is the
+    /// position that was appended, not necessarily the node now holding `key`.
+    ///
+    /// # Panics
+    /// Panics if the tree is not complete. Only the path to the new position is checked,
+    /// in O(log n); debug builds also check the whole tree.
+    pub fn heap_push(&mut self, key: K) -> usize {
+        debug_assert!(self.is_complete(Tree::ROOT_NODE), "Tree is not complete");
+        if self.is_empty() {
+            return self.allocate(key);
+        }
+
+        let (mut path, is_left) = self
+            .heap_position(self.len() + 1)
+            .expect("Tree is not complete");
+        let parent_id = *path.last().unwrap();
+        // the position after the last node is free in a complete tree
+        assert!(
+            self.child_mut(parent_id, is_left).is_none(),
+            "Tree is not complete"
+        );
+        let node_id = self.add_node(parent_id, key, is_left);
+        path.push(node_id);
+
+        // sift up: the path lists the ancestors of the new node from the root down
+        for i in (1..path.len()).rev() {
+            if self.get(path[i]) <= self.get(path[i - 1]) {
+                break;
+            }
+            self.swap_keys(path[i], path[i - 1]);
+        }
+
+        node_id
+    }
+
+    /// Removes and returns the largest key of a tree that is a max-heap, or `None` if
+    /// the tree is empty. The last node of the last level takes the place of the root
+    /// and its key is sifted down, so `is_max_heap(ROOT_NODE)` still holds afterwards.
+    ///
+    /// # Panics
+    /// Panics if the tree is not complete. Only the path to the last position is checked,
+    /// in O(log n); debug builds also check the whole tree.
+    pub fn heap_pop(&mut self) -> Option<K> {
+        debug_assert!(self.is_complete(Tree::ROOT_NODE), "Tree is not complete");
+        if self.len() <= 1 {
+            return self
+                .subtree(Tree::ROOT_NODE)
+                .map(|root| self.release(root).key);
+        }
+
+        let (path, is_left) = self
+            .heap_position(self.len())
+            .expect("Tree is not complete");
+        let parent_id = *path.last().unwrap();
+        let last = self
+            .child_mut(parent_id, is_left)
+            .take()
+            .expect("Tree is not complete");
+        self.swap_keys(Tree::ROOT_NODE, last);
+        let max = self.release(last).key;
+
+        // sift down, always towards the larger child
+        let mut node_id = Tree::ROOT_NODE;
+        loop {
+            let node = self.get_node(Some(node_id)).unwrap();
+            let largest = [node.id_left, node.id_right].into_iter().flatten().fold(
+                node_id,
+                |largest, child| {
+                    if self.get(child) > self.get(largest) {
+                        child
+                    } else {
+                        largest
+                    }
+                },
+            );
+            if largest == node_id {
+                break;
+            }
+            self.swap_keys(node_id, largest);
+            node_id = largest;
+        }
+
+        Some(max)
+    }
+
+    // Locates the node at `position` (counting from 1 in level-order) of a complete tree.
+    // Returns the ids from the root down to the parent of that position, and whether the
+    // position is the left child of its parent. The binary digits of `position` after the
+    // leading one spell the way from the root: 0 goes left, 1 goes right.
+    //
+    // Only the path is checked: fails with `TreeError::NotComplete` on the first node
+    // missing a child on the way, or missing the left sibling of a right position.
+    fn heap_position(&self, position: usize) -> Result<(Vec<usize>, bool), TreeError> {
+        let depth = position.ilog2();
+        let mut path = vec![Tree::ROOT_NODE];
+
+        for bit in (0..depth).rev() {
+            let node_id = *path.last().unwrap();
+            let node = self.get_node(Some(node_id)).unwrap();
+            let is_right = position >> bit & 1 == 1;
+            if is_right && node.id_left.is_none() {
+                return Err(TreeError::NotComplete(node_id));
+            }
+            if bit > 0 {
+                let child = if is_right {
+                    node.id_right
+                } else {
+                    node.id_left
+                };
+                path.push(child.ok_or(TreeError::NotComplete(node_id))?);
+            }
+        }
+
+        Ok((path, position & 1 == 0))
+    }
+}
+
+impl<K> Tree<K> {
+    /// Lists the keys of a complete tree in level-order, which is the implicit layout
+    /// of a heap: the children of the key at index `i` are at `2 * i + 1` and `2 * i + 2`.
+    /// Returns `TreeError::NotComplete` with the first node that follows an empty
+    /// position if the tree is not complete.
+    pub fn to_implicit_array(&self) -> Result<Vec<K>, TreeError>
+    where
+        K: Clone,
+    {
+        let mut keys = Vec::with_capacity(self.len());
+        let mut queue = VecDeque::from([self.subtree(Tree::ROOT_NODE)]);
+        let mut gap = false;
+
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = self.get_node(id) else {
+                gap = true;
+                continue;
+            };
+            if gap {
+                return Err(TreeError::NotComplete(id.unwrap()));
+            }
+
+            keys.push(node.key.clone());
+            queue.push_back(node.id_left);
+            queue.push_back(node.id_right);
+        }
+
+        Ok(keys)
+    }
+
+    /// Builds the complete tree whose keys in level-order are `keys`, the inverse of
+    /// [`Tree::to_implicit_array`]. The node holding the key at index `i` gets id `i`.
+    /// A heap-ordered sequence gives a tree for which `is_max_heap(ROOT_NODE)` holds.
+    pub fn from_implicit_array(keys: impl IntoIterator<Item = K>) -> Self {
+        let mut tree = Tree::empty();
+
+        for (index, key) in keys.into_iter().enumerate() {
+            let node_id = tree.allocate(key);
+            if index > 0 {
+                tree.set_child((index - 1) / 2, index % 2 == 1, Some(node_id));
+            }
+        }
+
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    #[test]
+    fn test_heap_push() {
+        let mut tree = Tree::with_root(10);
+
+        for key in [20, 5, 30, 30, 1, 15] {
+            tree.heap_push(key);
+            assert!(tree.is_max_heap(Tree::ROOT_NODE));
+        }
+
+        assert_eq!(tree.len(), 7);
+        assert_eq!(*tree.get(Tree::ROOT_NODE), 30);
+        assert_eq!(
+            tree.level_order(Tree::ROOT_NODE)
+                .map(|(_, key)| *key)
+                .collect::<Vec<_>>(),
+            vec![30, 30, 15, 10, 20, 1, 5]
+        );
+    }
+
+    #[test]
+    fn test_heap_pop() {
+        let mut tree = Tree::with_root(4);
+        for key in [8, 1, 9, 3, 7, 7, 2, 6, 5] {
+            tree.heap_push(key);
+        }
+
+        let mut popped = Vec::new();
+        while let Some(key) = tree.heap_pop() {
+            popped.push(key);
+            assert!(tree.is_max_heap(Tree::ROOT_NODE));
+        }
+
+        assert_eq!(popped, vec![9, 8, 7, 7, 6, 5, 4, 3, 2, 1]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.heap_pop(), None);
+
+        tree.heap_push(3);
+        assert_eq!(tree.heap_pop(), Some(3));
+    }
+
+    #[test]
+    fn test_heap_random_operations() {
+        let mut rng = Rng::new(3);
+        let mut next = || rng.below(1000) as u32;
+
+        let mut tree = Tree::with_root(next());
+        let mut expected = std::collections::BinaryHeap::from([*tree.get(Tree::ROOT_NODE)]);
+        for _ in 0..3000 {
+            if next() % 3 == 0 {
+                assert_eq!(tree.heap_pop(), expected.pop());
+            } else {
+                let key = next();
+                tree.heap_push(key);
+                expected.push(key);
+            }
+
+            assert!(tree.is_max_heap(Tree::ROOT_NODE));
+            assert_eq!(tree.len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_implicit_array() {
+        let keys = vec![9, 7, 8, 3, 5, 1];
+        let tree = Tree::from_implicit_array(keys.clone());
+        tree.assert_links();
+
+        assert_eq!(tree, Tree::parse_level_order("[9,7,8,3,5,1]").unwrap());
+        assert!(tree.is_max_heap(Tree::ROOT_NODE));
+        assert_eq!(*tree.get(4), 5);
+        assert_eq!(tree.to_implicit_array(), Ok(keys));
+
+        let mut tree = Tree::from_implicit_array(Vec::<u32>::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.to_implicit_array(), Ok(Vec::new()));
+
+        // the layout survives heap operations that reuse freed slots
+        for key in [4, 8, 1, 9] {
+            tree.heap_push(key);
+        }
+        tree.heap_pop();
+        tree.heap_push(6);
+        assert_eq!(tree.to_implicit_array(), Ok(vec![8, 6, 1, 4]));
+    }
+
+    #[test]
+    fn test_to_implicit_array_incomplete() {
+        let tree: Tree<u32> = Tree::parse_level_order("[9,7,8,3,null,1]").unwrap();
+        assert_eq!(tree.to_implicit_array(), Err(TreeError::NotComplete(4)));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[9,null,8]").unwrap();
+        assert_eq!(tree.to_implicit_array(), Err(TreeError::NotComplete(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tree is not complete")]
+    fn test_heap_push_incomplete() {
+        let mut tree = Tree::with_root(10);
+        tree.add_node(Tree::ROOT_NODE, 5, false);
+        tree.add_node(1, 4, false);
+        tree.heap_push(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tree is not complete")]
+    fn test_heap_push_hole_off_path() {
+        // the next position is the left child of 5, but the hole is under 3
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,3,null,null,2]").unwrap();
+        tree.heap_push(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tree is not complete")]
+    fn test_heap_pop_incomplete() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,3,null,4]").unwrap();
+        tree.heap_pop();
+    }
+
+    #[test]
+    fn test_heap_position() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,3,4,1,2]").unwrap();
+        assert_eq!(tree.heap_position(7), Ok((vec![0, 2], false)));
+        assert_eq!(tree.heap_position(4), Ok((vec![0, 1], true)));
+
+        // the left sibling of position 5 is missing
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,3,null,null,2]").unwrap();
+        assert_eq!(tree.heap_position(5), Err(TreeError::NotComplete(1)));
+        let tree: Tree<u32> = Tree::parse_level_order("[10,null,3,null,2]").unwrap();
+        assert_eq!(tree.heap_position(7), Err(TreeError::NotComplete(0)));
+    }
+}
@@ -0,0 +1,204 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * lowest common ancestor queries by binary lifting
+ */
+
+use crate::Tree;
+
+/// Ancestor queries over a snapshot of a `Tree`.
+/// Built in O(n log n), it answers every query in O(log n) using binary lifting:
+/// `up[j][v]` is the ancestor of `v` that is `2^j` levels above it, or the root if
+/// `v` is less than `2^j` levels deep. Changes to the tree after the preprocessing
+/// are not reflected in the answers.
+#[derive(Debug, Clone)]
+pub struct Lca {
+    depths: Vec<Option<usize>>,
+    up: Vec<Vec<usize>>,
+}
+
+impl Lca {
+    /// Records the parent and the depth of every node of `tree`.
+    pub fn new<K>(tree: &Tree<K>) -> Self {
+        let mut depths = vec![None; tree.nodes.len()];
+        let mut parents = vec![Tree::ROOT_NODE; tree.nodes.len()];
+        let mut max_depth: usize = 0;
+
+        for (id, _) in tree.level_order(Tree::ROOT_NODE) {
+            let depth = if id == Tree::ROOT_NODE {
+                0
+            } else {
+                depths[parents[id]].unwrap() + 1
+            };
+            depths[id] = Some(depth);
+            max_depth = max_depth.max(depth);
+
+            let node = tree.get_node(Some(id)).unwrap();
+            for child in [node.id_left, node.id_right].into_iter().flatten() {
+                parents[child] = id;
+            }
+        }
+
+        let levels = (usize::BITS - max_depth.leading_zeros()).max(1) as usize;
+        let mut up = vec![parents];
+        for j in 1..levels {
+            let previous = &up[j - 1];
+            let next = previous
+                .iter()
+                .map(|&ancestor| previous[ancestor])
+                .collect();
+            up.push(next);
+        }
+
+        Self { depths, up }
+    }
+
+    /// Returns the depth of `node_id`, the root having depth 0.
+    ///
+    /// # Panics
+    /// Panics if `node_id` was not a node of the tree.
+    pub fn depth(&self, node_id: usize) -> usize {
+        self.depths
+            .get(node_id)
+            .copied()
+            .flatten()
+            .expect("Node id is not in the tree")
+    }
+
+    /// Returns the lowest node that has both `u` and `v` in its subtree.
+    ///
+    /// # Panics
+    /// Panics if `u` or `v` was not a node of the tree.
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (mut u, mut v) = if self.depth(u) >= self.depth(v) {
+            (u, v)
+        } else {
+            (v, u)
+        };
+
+        u = self.lift(u, self.depth(u) - self.depth(v));
+        if u == v {
+            return u;
+        }
+
+        // climb as long as the two nodes stay apart, then the parent is the answer
+        for level in self.up.iter().rev() {
+            if level[u] != level[v] {
+                u = level[u];
+                v = level[v];
+            }
+        }
+
+        self.up[0][u]
+    }
+
+    /// Returns the number of edges on the path between `u` and `v`.
+    ///
+    /// # Panics
+    /// Panics if `u` or `v` was not a node of the tree.
+    pub fn distance(&self, u: usize, v: usize) -> usize {
+        let ancestor = self.lca(u, v);
+        self.depth(u) + self.depth(v) - 2 * self.depth(ancestor)
+    }
+
+    /// Returns the ancestor `k` levels above `node_id`, which is `node_id` itself for
+    /// `k == 0`, or `None` if `node_id` is less than `k` levels deep.
+    ///
+    /// # Panics
+    /// Panics if `node_id` was not a node of the tree.
+    pub fn kth_ancestor(&self, node_id: usize, k: usize) -> Option<usize> {
+        if k > self.depth(node_id) {
+            return None;
+        }
+
+        Some(self.lift(node_id, k))
+    }
+
+    // Climbs `k` levels, with `k` not larger than the depth of `node_id`
+    fn lift(&self, mut node_id: usize, k: usize) -> usize {
+        for (j, level) in self.up.iter().enumerate() {
+            if k >> j & 1 == 1 {
+                node_id = level[node_id];
+            }
+        }
+
+        node_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lca() {
+        // the key of every node is its id
+        let tree: Tree<u32> =
+            Tree::parse_level_order("[0,1,2,3,4,5,null,6,null,null,null,7,8]").unwrap();
+        let lca = Lca::new(&tree);
+
+        assert_eq!(lca.lca(6, 4), 1);
+        assert_eq!(lca.lca(4, 6), 1);
+        assert_eq!(lca.lca(7, 8), 5);
+        assert_eq!(lca.lca(6, 8), 0);
+        assert_eq!(lca.lca(3, 6), 3);
+        assert_eq!(lca.lca(2, 2), 2);
+        assert_eq!(lca.lca(0, 7), 0);
+    }
+
+    #[test]
+    fn test_distance_and_depth() {
+        let tree: Tree<u32> =
+            Tree::parse_level_order("[0,1,2,3,4,5,null,6,null,null,null,7,8]").unwrap();
+        let lca = Lca::new(&tree);
+
+        assert_eq!(lca.depth(0), 0);
+        assert_eq!(lca.depth(7), 3);
+        assert_eq!(lca.distance(6, 8), 6);
+        assert_eq!(lca.distance(7, 8), 2);
+        assert_eq!(lca.distance(3, 3), 0);
+        assert_eq!(lca.distance(0, 6), 3);
+    }
+
+    #[test]
+    fn test_kth_ancestor() {
+        let tree: Tree<u32> =
+            Tree::parse_level_order("[0,1,2,3,4,5,null,6,null,null,null,7,8]").unwrap();
+        let lca = Lca::new(&tree);
+
+        assert_eq!(lca.kth_ancestor(7, 0), Some(7));
+        assert_eq!(lca.kth_ancestor(7, 1), Some(5));
+        assert_eq!(lca.kth_ancestor(7, 3), Some(0));
+        assert_eq!(lca.kth_ancestor(7, 4), None);
+        assert_eq!(lca.kth_ancestor(0, 1), None);
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        let mut tree = Tree::with_root(0_u32);
+        let mut last = Tree::ROOT_NODE;
+        for key in 1..100_000 {
+            last = tree.add_node(last, key, key % 2 == 0);
+        }
+        // the chain continues from 50_000 to the right, as 50_001 is odd
+        let branch = tree.add_node(50_000, 0, true);
+
+        let lca = Lca::new(&tree);
+        assert_eq!(lca.depth(last), 99_999);
+        assert_eq!(lca.kth_ancestor(last, 99_999), Some(Tree::ROOT_NODE));
+        assert_eq!(lca.kth_ancestor(last, 12_345), Some(99_999 - 12_345));
+        assert_eq!(lca.lca(last, branch), 50_000);
+        assert_eq!(lca.distance(last, branch), 50_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Node id is not in the tree")]
+    fn test_removed_node() {
+        let mut tree: Tree<u32> =
+            Tree::parse_level_order("[0,1,2,3,4,5,null,6,null,null,null,7,8]").unwrap();
+        tree.remove_leaf(4).unwrap();
+        Lca::new(&tree).depth(4);
+    }
+}
@@ -4,38 +4,116 @@
  * Autumn 2023
  */
 
-struct Node {
-    key: u32,
+use std::ops::Add;
+
+mod aggregate;
+mod analysis;
+mod avl;
+mod bst;
+mod error;
+mod generate;
+mod heap;
+mod lca;
+mod navigation;
+mod parse;
+#[cfg(test)]
+mod property_tests;
+mod ranked;
+mod rebuild;
+mod removal;
+mod render;
+mod report;
+mod shape;
+mod structure;
+mod summary;
+mod transform;
+mod traversal;
+mod visitor;
+
+pub use aggregate::CheckedAdd;
+pub use avl::Avl;
+pub use bst::Bst;
+pub use error::{ParseError, TreeError};
+pub use generate::Generator;
+pub use lca::Lca;
+pub use ranked::{KeyRange, RankedBst};
+pub use render::Highlight;
+pub use report::{Reason, Violation};
+pub use summary::Summarized;
+pub use traversal::{InOrder, LevelOrder, PostOrder, PreOrder};
+pub use visitor::Visitor;
+
+use visitor::{BalanceCheck, BstCheck, Closures, HeapCheck};
+
+#[derive(Debug, Clone)]
+struct Node<K> {
+    key: K,
     id_left: Option<usize>,
     id_right: Option<usize>,
+    id_parent: Option<usize>,
 }
 
-impl Node {
-    fn new(key: u32) -> Self {
+impl<K> Node<K> {
+    fn new(key: K) -> Self {
         Self {
             key,
             id_left: None,
             id_right: None,
+            id_parent: None,
         }
     }
 }
 
-struct Tree {
-    nodes: Vec<Node>,
+#[derive(Debug, Clone)]
+pub struct Tree<K> {
+    // a removed node leaves a vacant slot behind, so the ids of the other nodes stay stable
+    nodes: Vec<Option<Node<K>>>,
+    // ids of the vacant slots, reused by `add_node`
+    free: Vec<usize>,
+}
+
+// The id of the root does not depend on the key type, so it lives in a
+// non-generic impl. This keeps `Tree::ROOT_NODE` usable without naming `K`.
+impl Tree<()> {
+    pub const ROOT_NODE: usize = 0;
 }
 
 /// This a representation of a tree.
 /// Every node has an implicit id, which is its position on the vector `nodes`.
 /// Every node has a key and at most two children. The ids of the children are
 /// stored in `id_left` and `id_right`. These ids are `None` if the child does not exit.
-impl Tree {
-    pub fn with_root(key: u32) -> Self {
+/// Every node also links back to its parent in `id_parent`, which is `None` for the root.
+/// The tree is generic over the type `K` of its keys; every check only asks for
+/// the capabilities of `K` it actually needs.
+///
+/// Removing a node frees its id, which may be handed out again by a later `add_node`.
+/// The root always has the id `ROOT_NODE`; a tree whose root was removed is empty.
+impl<K> Tree<K> {
+    pub fn with_root(key: K) -> Self {
         Self {
-            nodes: vec![Node::new(key)],
+            nodes: vec![Some(Node::new(key))],
+            free: Vec::new(),
         }
     }
 
-    pub const ROOT_NODE: usize = 0;
+    // A tree without any node, as built from empty input or left by removing the root
+    fn empty() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Returns the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    /// Returns `true` if the tree has no node, e.g. because it was built from empty
+    /// input or its root has been removed.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
 
     /// Adds a child to the node with `parent_id` and returns the id of the new node.
     /// The new node has the specified `key`. The new node is the left child of the node `parent_id`
@@ -43,116 +121,217 @@ impl Tree {
     ///
     /// # Panics
     /// Panics if the `parent_id` does not exist, or if the node `parent_id ` has the child already set.
-    pub fn add_node(&mut self, parent_id: usize, key: u32, is_left: bool) -> usize {
-        assert!(
-            parent_id < self.nodes.len(),
-            "Parent node id does not exist"
-        );
-        if is_left {
-            assert!(
-                self.nodes[parent_id].id_left.is_none(),
-                "Parent node has the child already set"
-            );
-        } else {
-            assert!(
-                self.nodes[parent_id].id_right.is_none(),
-                "Parent node has the right child already set"
-            );
+    /// See [`Tree::try_add_node`] for the non-panicking version.
+    pub fn add_node(&mut self, parent_id: usize, key: K, is_left: bool) -> usize {
+        match self.try_add_node(parent_id, key, is_left) {
+            Ok(child_id) => child_id,
+            Err(err) => panic!("{err}"),
         }
+    }
 
-        let child_id = self.nodes.len();
-        self.nodes.push(Node::new(key));
-
-        let child = if is_left {
-            &mut self.nodes[parent_id].id_left
+    /// Same as [`Tree::add_node`], but reports an invalid `parent_id` or an occupied
+    /// child slot as an error instead of panicking. The tree is unchanged on error.
+    pub fn try_add_node(
+        &mut self,
+        parent_id: usize,
+        key: K,
+        is_left: bool,
+    ) -> Result<usize, TreeError> {
+        let parent = self
+            .try_node(parent_id)
+            .map_err(|_| TreeError::UnknownParent(parent_id))?;
+        let slot = if is_left {
+            parent.id_left
         } else {
-            &mut self.nodes[parent_id].id_right
+            parent.id_right
         };
+        if slot.is_some() {
+            return Err(TreeError::SlotOccupied { parent_id, is_left });
+        }
 
-        *child = Some(child_id);
+        let child_id = self.allocate(key);
+        self.set_child(parent_id, is_left, Some(child_id));
 
-        child_id
+        Ok(child_id)
     }
 
-    /// Returns the sum of all the keys in the tree
-    pub fn sum(&self) -> u32 {
-        self.rec_sum(Some(0))
+    /// Returns the key of the node `node_id`.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist. See [`Tree::try_get`] for the non-panicking version.
+    pub fn get(&self, node_id: usize) -> &K {
+        &self.get_node(Some(node_id)).unwrap().key
     }
 
-    /// A private recursive function that computes the sum of
-    /// nodes in the subtree rooted at `node_id`.
-    fn rec_sum(&self, node_id: Option<usize>) -> u32 {
-        if let Some(id) = node_id {
-            assert!(id < self.nodes.len(), "Node id is out of range");
-            let node = &self.nodes[id];
-
-            let sum_left = self.rec_sum(node.id_left);
-            let sum_right = self.rec_sum(node.id_right);
-
-            return sum_left + sum_right + node.key;
-        }
+    /// Returns the key of the node `node_id`, or an error if the node does not exist.
+    pub fn try_get(&self, node_id: usize) -> Result<&K, TreeError> {
+        self.try_node(node_id).map(|node| &node.key)
+    }
 
-        0
+    /// Returns the sum of all the keys in the tree.
+    /// An empty subtree contributes `K::default()`, i.e. `0` for the numeric types.
+    pub fn sum(&self) -> K
+    where
+        K: Clone + Add<Output = K> + Default,
+    {
+        self.evaluate(self.subtree(0), K::default, |node, sum_left, sum_right| {
+            sum_left + sum_right + node.key.clone()
+        })
     }
 
     // A private method that, for given index, returns a Node
     // and handles potential error states
-    fn get_node(&self, node_id: Option<usize>) -> Option<&Node> {
+    fn get_node(&self, node_id: Option<usize>) -> Option<&Node<K>> {
         match node_id {
-            Some(id) => {
-                assert!(id < self.nodes.len(), "Node id is out of range");
-                Some(&self.nodes[id])
-            }
+            Some(id) => match self.try_node(id) {
+                Ok(node) => Some(node),
+                Err(err) => panic!("{err}"),
+            },
             None => None,
         }
     }
 
+    // The fallible counterpart of `get_node`
+    fn try_node(&self, node_id: usize) -> Result<&Node<K>, TreeError> {
+        match self.nodes.get(node_id) {
+            Some(Some(node)) => Ok(node),
+            Some(None) => Err(TreeError::NodeRemoved(node_id)),
+            None => Err(TreeError::IdOutOfRange(node_id)),
+        }
+    }
+
+    // The mutable counterpart of `get_node`, for ids known to be valid
+    fn get_node_mut(&mut self, node_id: usize) -> &mut Node<K> {
+        match self.nodes.get_mut(node_id) {
+            Some(Some(node)) => node,
+            _ => panic!("{}", TreeError::IdOutOfRange(node_id)),
+        }
+    }
+
+    // The link from `parent_id` to its left or right child
+    fn child_mut(&mut self, parent_id: usize, is_left: bool) -> &mut Option<usize> {
+        let parent = self.get_node_mut(parent_id);
+        if is_left {
+            &mut parent.id_left
+        } else {
+            &mut parent.id_right
+        }
+    }
+
+    // Links `child` as the left or right child of `parent_id`, updating both directions.
+    // Whatever occupied the slot before keeps its own link to `parent_id`.
+    fn set_child(&mut self, parent_id: usize, is_left: bool, child: Option<usize>) {
+        *self.child_mut(parent_id, is_left) = child;
+        if let Some(child) = child {
+            self.get_node_mut(child).id_parent = Some(parent_id);
+        }
+    }
+
+    // Returns the parent of `node_id` and whether `node_id` is its left child
+    fn parent_link(&self, node_id: usize) -> Option<(usize, bool)> {
+        let parent_id = self.get_node(Some(node_id)).unwrap().id_parent?;
+        let is_left = self.get_node(Some(parent_id)).unwrap().id_left == Some(node_id);
+
+        Some((parent_id, is_left))
+    }
+
+    // Stores a new node without any link in a vacant slot, or at the end of the arena.
+    // In an empty tree, the new node becomes the root.
+    fn allocate(&mut self, key: K) -> usize {
+        match self.free.pop() {
+            Some(node_id) => {
+                self.nodes[node_id] = Some(Node::new(key));
+                node_id
+            }
+            None => {
+                self.nodes.push(Some(Node::new(key)));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // Moves the node out of the arena and makes its id vacant. The caller is responsible
+    // for the links to and from the node. Releasing the root empties the whole tree.
+    fn release(&mut self, node_id: usize) -> Node<K> {
+        let node = self.nodes[node_id].take().unwrap();
+        if node_id == Tree::ROOT_NODE {
+            self.nodes.clear();
+            self.free.clear();
+        } else {
+            self.free.push(node_id);
+        }
+
+        node
+    }
+
+    // Exchanges the keys of two nodes, leaving the shape of the tree untouched
+    fn swap_keys(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let (low, high) = (a.min(b), a.max(b));
+        let (head, tail) = self.nodes.split_at_mut(high);
+        let low = head[low].as_mut().unwrap();
+        let high = tail[0].as_mut().unwrap();
+        std::mem::swap(&mut low.key, &mut high.key);
+    }
+
+    // Checks that every link to a child is matched by the link back to the parent
+    #[cfg(test)]
+    fn assert_links(&self) {
+        for (id, node) in self.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            if id == Tree::ROOT_NODE {
+                assert_eq!(node.id_parent, None);
+            }
+            for child in [node.id_left, node.id_right].into_iter().flatten() {
+                assert_eq!(self.get_node(Some(child)).unwrap().id_parent, Some(id));
+            }
+            if let Some(parent) = node.id_parent {
+                let parent = self.get_node(Some(parent)).unwrap();
+                assert!(parent.id_left == Some(id) || parent.id_right == Some(id));
+            }
+        }
+    }
+
+    // Maps the id of a subtree root to the starting point of a traversal.
+    // The root of an empty tree is an empty subtree rather than an invalid id.
+    fn subtree(&self, node_id: usize) -> Option<usize> {
+        if self.is_empty() && node_id == Tree::ROOT_NODE {
+            None
+        } else {
+            Some(node_id)
+        }
+    }
+
+    // Same as `Tree::visit`, from a possibly empty subtree, with the visitor given as
+    // two closures: `leaf` produces the result of an empty subtree and `combine` merges
+    // the results of the left and right subtree of a node into the result of the node.
+    fn evaluate<'a, R>(
+        &'a self,
+        node_id: Option<usize>,
+        leaf: impl Fn() -> R,
+        combine: impl FnMut(&'a Node<K>, R, R) -> R,
+    ) -> R {
+        let mut visitor = Closures {
+            tree: self,
+            leaf,
+            combine,
+        };
+        self.walk(node_id, &mut visitor)
+    }
+
     // a method to check if the binary tree is a Binary Search Tree
     //
     // parameters:
     //  - node_id: ID of node to check
-    pub fn is_bst(&self, node_id: usize) -> bool {
-        self.rec_is_bst(Some(node_id)).0
-    }
-
-    // returns:
-    //  - whether the processed node is BST
-    //  - maximum value in subtree
-    //  - minimum value in subtree
-    fn rec_is_bst(&self, node_id: Option<usize>) -> (bool, Option<u32>, Option<u32>) {
-        if let Some(node) = self.get_node(node_id) {
-            let (left_is_bst, max_left, min_left) = self.rec_is_bst(node.id_left);
-            let (right_is_bst, max_right, min_right) = self.rec_is_bst(node.id_right);
-
-            // calculate minimum and maximum of node and its subtree
-            let node_max = match max_right {
-                Some(max_right) => max_right,
-                None => node.key,
-            };
-            let node_min = match min_left {
-                Some(min_left) => min_left,
-                None => node.key,
-            };
-
-            // chceck for BST property of current node
-            let left_satisfies_bst = match max_left {
-                Some(max_left) => max_left < node.key,
-                None => true,
-            };
-            let right_satisfies_bst = match min_right {
-                Some(min_right) => node.key < min_right,
-                None => true,
-            };
-            let node_is_bst = left_satisfies_bst && right_satisfies_bst;
-
-            (
-                left_is_bst && right_is_bst && node_is_bst,
-                Some(node_max),
-                Some(node_min),
-            )
-        } else {
-            (true, None, None)
-        }
+    pub fn is_bst(&self, node_id: usize) -> bool
+    where
+        K: Ord,
+    {
+        let (is_bst, _, _) = self.visit(node_id, &mut BstCheck);
+
+        is_bst
     }
 
     // a method to check if the binary tree is balanced
@@ -161,27 +340,9 @@ impl Tree {
     // parameters:
     //  - node_id: ID of node to check
     pub fn is_balanced(&self, node_id: usize) -> bool {
-        self.rec_is_balanced(Some(node_id)).0
-    }
+        let (is_balanced, _) = self.visit(node_id, &mut BalanceCheck);
 
-    // returns:
-    //  - whether the processed subtree is balanced
-    //  - maximum distance to leaf (height)
-    fn rec_is_balanced(&self, node_id: Option<usize>) -> (bool, usize) {
-        if let Some(node) = self.get_node(node_id) {
-            let (left_is_balanced, max_height_left) = self.rec_is_balanced(node.id_left);
-            let (right_is_balanced, max_height_right) = self.rec_is_balanced(node.id_right);
-
-            let node_is_balanced = max_height_left.abs_diff(max_height_right) <= 1;
-            let node_max_height = max_height_left.max(max_height_right) + 1;
-
-            (
-                left_is_balanced && right_is_balanced && node_is_balanced,
-                node_max_height,
-            )
-        } else {
-            (true, 0)
-        }
+        is_balanced
     }
 
     // a method to check if the binary tree is a max-heap
@@ -190,54 +351,39 @@ impl Tree {
     //
     // parameters:
     //  - node_id: ID of node to check
-    pub fn is_max_heap(&self, node_id: usize) -> bool {
-        self.rec_is_max_heap(Some(node_id)).0
-    }
-
-    // returns:
-    //  - whether the processed node satisfies max heap property
-    //  - value of the processed node
-    //  - max height of the tree
-    //  - min height of the tree
-    fn rec_is_max_heap(&self, node_id: Option<usize>) -> (bool, Option<u32>, usize, usize) {
-        if let Some(node) = self.get_node(node_id) {
-            let (left_is_max_heap, left_value, min_height_left, max_height_left) =
-                self.rec_is_max_heap(node.id_left);
-            let (right_is_max_heap, right_value, min_height_right, max_height_right) =
-                self.rec_is_max_heap(node.id_right);
-
-            // check for max-heap property
-            let left_max_heap = match left_value {
-                Some(left_value) => left_value <= node.key,
-                None => true,
-            };
-            let right_max_heap = match right_value {
-                Some(right_value) => right_value <= node.key,
-                None => true,
-            };
-
-            // check for completeness property
-            let is_complete = (max_height_left.wrapping_sub(min_height_right) <= 1)
-                && (max_height_left >= min_height_right);
-
-            let node_is_max_heap = left_max_heap && right_max_heap && is_complete;
-
-            let min_node_height = min_height_left.min(min_height_right) + 1;
-            let max_node_height = max_height_left.max(max_height_right) + 1;
-
-            (
-                left_is_max_heap && right_is_max_heap && node_is_max_heap,
-                Some(node.key),
-                min_node_height,
-                max_node_height,
-            )
-        } else {
-            (true, None, 0, 0)
-        }
+    pub fn is_max_heap(&self, node_id: usize) -> bool
+    where
+        K: PartialOrd,
+    {
+        self.is_heap_by(node_id, |parent, child| child <= parent)
+    }
+
+    // a method to check if the binary tree is a min-heap
+    // Same as a max-heap, but the key of every node is smaller than or equal to the keys of its children.
+    //
+    // parameters:
+    //  - node_id: ID of node to check
+    pub fn is_min_heap(&self, node_id: usize) -> bool
+    where
+        K: PartialOrd,
+    {
+        self.is_heap_by(node_id, |parent, child| parent <= child)
+    }
+
+    /// Checks if the subtree rooted at `node_id` is a complete binary tree in which
+    /// `precedes(parent, child)` holds for the key of every node and of its children.
+    /// `precedes` should be reflexive, e.g. `|parent, child| parent.priority >= child.priority`
+    /// for a queue that serves the highest priority first.
+    pub fn is_heap_by(&self, node_id: usize, precedes: impl FnMut(&K, &K) -> bool) -> bool {
+        let (is_heap, _, _) = self.visit(node_id, &mut HeapCheck(precedes));
+
+        is_heap
     }
 }
 
+// the original tests compare checks with `true` and `false` and keep unused ids
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, unused_variables)]
 mod tests {
     use super::*;
 
@@ -422,4 +568,167 @@ mod tests {
         tree.add_node(new_node, 20, false);
         assert_eq!(tree.is_max_heap(Tree::ROOT_NODE), true);
     }
+
+    #[test]
+    fn test_generic_keys() {
+        let mut tree: Tree<i64> = Tree::with_root(-10);
+        tree.add_node(Tree::ROOT_NODE, -25, true);
+        tree.add_node(Tree::ROOT_NODE, 40, false);
+
+        assert_eq!(tree.sum(), 5);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+
+        let mut tree = Tree::with_root(String::from("m"));
+        tree.add_node(Tree::ROOT_NODE, String::from("c"), true);
+        tree.add_node(Tree::ROOT_NODE, String::from("x"), false);
+
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(tree.is_balanced(Tree::ROOT_NODE));
+
+        // keys only need to be partially ordered for the heap check
+        let mut tree = Tree::with_root(2.5_f64);
+        tree.add_node(Tree::ROOT_NODE, 1.5, true);
+        tree.add_node(Tree::ROOT_NODE, 2.5, false);
+
+        assert!(tree.is_max_heap(Tree::ROOT_NODE));
+        assert_eq!(tree.sum(), 6.5);
+    }
+
+    #[test]
+    fn test_degenerate_tree() {
+        // a linked-list shaped tree, far deeper than the call stack would allow
+        let depth = 1_000_000;
+        let mut tree = Tree::with_root(0_u64);
+        let mut last = Tree::ROOT_NODE;
+        for key in 1..depth {
+            last = tree.add_node(last, key, false);
+        }
+
+        assert_eq!(tree.sum(), depth * (depth - 1) / 2);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(!tree.is_balanced(Tree::ROOT_NODE));
+        assert!(tree.is_balanced(last));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+
+        // the same chain going left, with decreasing keys
+        let mut tree = Tree::with_root(depth);
+        let mut last = Tree::ROOT_NODE;
+        for key in (1..depth).rev() {
+            last = tree.add_node(last, key, true);
+        }
+
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+        assert!(tree.is_max_heap(last));
+    }
+
+    #[test]
+    fn test_try_add_node() {
+        let mut tree = Tree::with_root(10);
+
+        assert_eq!(tree.try_add_node(Tree::ROOT_NODE, 5, true), Ok(1));
+        assert_eq!(
+            tree.try_add_node(Tree::ROOT_NODE, 6, true),
+            Err(TreeError::SlotOccupied {
+                parent_id: Tree::ROOT_NODE,
+                is_left: true
+            })
+        );
+        assert_eq!(
+            tree.try_add_node(7, 6, false),
+            Err(TreeError::UnknownParent(7))
+        );
+        assert_eq!(tree.try_add_node(1, 6, false), Ok(2));
+
+        // rejected insertions leave the tree untouched
+        assert_eq!(tree.sum(), 21);
+    }
+
+    #[test]
+    fn test_try_get() {
+        let mut tree = Tree::with_root(10);
+        let child = tree.add_node(Tree::ROOT_NODE, 5, false);
+
+        assert_eq!(tree.try_get(child), Ok(&5));
+        assert_eq!(*tree.get(Tree::ROOT_NODE), 10);
+        assert_eq!(tree.try_get(2), Err(TreeError::IdOutOfRange(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "has the right child already set")]
+    fn test_add_node_panics() {
+        let mut tree = Tree::with_root(10);
+        tree.add_node(Tree::ROOT_NODE, 5, false);
+        tree.add_node(Tree::ROOT_NODE, 6, false);
+    }
+
+    #[test]
+    fn test_is_max_heap_gap_in_last_level() {
+        // found by the randomized oracle tests: both subtrees are complete and their
+        // heights are close, but the last level has a hole
+        //     o
+        //  o     o
+        // / \   o \
+        let tree: Tree<u32> = Tree::parse_level_order("[0,0,0,null,null,0]").unwrap();
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[0,0,0,0]").unwrap();
+        assert!(tree.is_max_heap(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_is_min_heap() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,3,2,7,3]").unwrap();
+        assert!(tree.is_min_heap(Tree::ROOT_NODE));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+        assert!(tree.is_max_heap(3));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[1,3,2,7,0]").unwrap();
+        assert!(!tree.is_min_heap(Tree::ROOT_NODE));
+        let tree: Tree<u32> = Tree::parse_level_order("[1,3,2,null,4]").unwrap();
+        assert!(!tree.is_min_heap(Tree::ROOT_NODE));
+
+        // incomparable keys break the ordering
+        let tree: Tree<f64> = Tree::parse_level_order("[1.0,NaN]").unwrap();
+        assert!(!tree.is_min_heap(Tree::ROOT_NODE));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_is_heap_by() {
+        #[derive(Debug)]
+        struct Task {
+            priority: u8,
+            name: &'static str,
+        }
+
+        let mut tree = Tree::with_root(Task {
+            priority: 9,
+            name: "deploy",
+        });
+        tree.add_node(
+            Tree::ROOT_NODE,
+            Task {
+                priority: 4,
+                name: "build",
+            },
+            true,
+        );
+        tree.add_node(
+            Tree::ROOT_NODE,
+            Task {
+                priority: 9,
+                name: "alert",
+            },
+            false,
+        );
+
+        assert!(
+            tree.is_heap_by(Tree::ROOT_NODE, |parent, child| parent.priority
+                >= child.priority)
+        );
+        assert!(!tree.is_heap_by(Tree::ROOT_NODE, |parent, child| parent.name <= child.name));
+        assert!(tree.is_heap_by(2, |_, _| false));
+    }
 }
@@ -0,0 +1,127 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * upward navigation in a `Tree` through the parent links
+ */
+
+use crate::Tree;
+
+impl<K> Tree<K> {
+    /// Returns the parent of `node_id`, or `None` for the root.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn parent(&self, node_id: usize) -> Option<usize> {
+        self.get_node(Some(node_id)).unwrap().id_parent
+    }
+
+    /// Returns the other child of the parent of `node_id`, if there is one.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn sibling(&self, node_id: usize) -> Option<usize> {
+        let (parent_id, is_left) = self.parent_link(node_id)?;
+        let parent = self.get_node(Some(parent_id)).unwrap();
+
+        if is_left {
+            parent.id_right
+        } else {
+            parent.id_left
+        }
+    }
+
+    /// Returns the number of edges between `node_id` and the root.
+    /// This climbs the tree, so it takes time proportional to the depth.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn depth(&self, node_id: usize) -> usize {
+        self.path_to_root(node_id).len() - 1
+    }
+
+    /// Returns the ids from `node_id` up to the root, both included.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn path_to_root(&self, node_id: usize) -> Vec<usize> {
+        let mut path = vec![node_id];
+        while let Some(parent_id) = self.parent(*path.last().unwrap()) {
+            path.push(parent_id);
+        }
+
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Avl, Bst};
+
+    #[test]
+    fn test_navigation() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(tree.parent(Tree::ROOT_NODE), None);
+        assert_eq!(tree.parent(4), Some(1));
+        assert_eq!(tree.sibling(3), Some(4));
+        assert_eq!(tree.sibling(4), Some(3));
+        assert_eq!(tree.sibling(5), None);
+        assert_eq!(tree.sibling(Tree::ROOT_NODE), None);
+        assert_eq!(tree.depth(Tree::ROOT_NODE), 0);
+        assert_eq!(tree.depth(5), 2);
+        assert_eq!(tree.path_to_root(4), vec![4, 1, 0]);
+    }
+
+    #[test]
+    fn test_links_after_removal() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        let detached = tree.detach_subtree(1).unwrap();
+        detached.assert_links();
+        assert_eq!(detached.parent(Tree::ROOT_NODE), None);
+        assert_eq!(detached.path_to_root(2), vec![2, 0]);
+
+        tree.assert_links();
+        tree.add_node(Tree::ROOT_NODE, 4, true);
+        let (leaf, _) = tree.replace_child(2, 21, true).unwrap();
+        tree.assert_links();
+        assert_eq!(tree.path_to_root(leaf), vec![leaf, 2, 0]);
+
+        tree.compact();
+        tree.assert_links();
+        assert_eq!(tree.sibling(1), Some(2));
+    }
+
+    #[test]
+    fn test_links_in_search_trees() {
+        let mut bst = Bst::new();
+        let mut avl = Avl::new();
+        for key in [50, 20, 80, 10, 30, 70, 90, 25, 35, 5] {
+            bst.insert(key);
+            avl.insert(key);
+            bst.tree().assert_links();
+            avl.tree().assert_links();
+        }
+        for key in [50, 20, 10, 80, 90, 70] {
+            bst.remove(&key);
+            avl.remove(&key);
+            bst.tree().assert_links();
+            avl.tree().assert_links();
+        }
+    }
+
+    #[test]
+    fn test_links_in_heap() {
+        let mut tree = Tree::with_root(0);
+        for key in [5, 3, 9, 1, 7, 8, 2] {
+            tree.heap_push(key);
+            tree.assert_links();
+        }
+        while tree.heap_pop().is_some() {
+            tree.assert_links();
+        }
+    }
+}
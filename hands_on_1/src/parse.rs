@@ -0,0 +1,266 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * constructors building a `Tree` from compact descriptions
+ */
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use crate::{ParseError, Tree};
+
+impl<K> Tree<K> {
+    /// Builds a tree from its level-order listing, where `None` marks a missing child,
+    /// e.g. `[10, 5, 22, null, 7, 20]` as used by LeetCode. Every present node consumes
+    /// the next two entries for its left and right child. Trailing `None`s may be omitted.
+    /// The ids of the nodes are assigned in level-order; an empty listing gives an empty tree.
+    pub fn from_level_order(keys: impl IntoIterator<Item = Option<K>>) -> Result<Self, ParseError> {
+        let mut keys = keys.into_iter().enumerate();
+        let mut tree = match keys.next() {
+            Some((_, Some(key))) => Tree::with_root(key),
+            Some((_, None)) => return Err(ParseError::MissingRoot),
+            None => return Ok(Tree::empty()),
+        };
+
+        // nodes whose children were not read yet, together with the side to fill next
+        let mut parents = VecDeque::from([(Tree::ROOT_NODE, true)]);
+        for (index, key) in keys {
+            let (parent_id, is_left) = match parents.pop_front() {
+                Some(parent) => parent,
+                None if key.is_none() => continue,
+                None => return Err(ParseError::Orphan(index)),
+            };
+            if is_left {
+                parents.push_front((parent_id, false));
+            }
+
+            if let Some(key) = key {
+                let child_id = tree.add_node(parent_id, key, is_left);
+                parents.push_back((child_id, true));
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Parses a level-order listing written as text, e.g. `"[10,5,22,null,7,20]"`.
+    /// The brackets are optional and the entries are separated by commas.
+    /// See [`Tree::from_level_order`] for how the listing is interpreted.
+    pub fn parse_level_order(text: &str) -> Result<Self, ParseError>
+    where
+        K: FromStr,
+    {
+        let text = text.trim();
+        let text = text.strip_prefix('[').unwrap_or(text);
+        let text = text.strip_suffix(']').unwrap_or(text);
+        if text.trim().is_empty() {
+            return Ok(Tree::empty());
+        }
+
+        let keys = text
+            .split(',')
+            .map(str::trim)
+            .enumerate()
+            .map(|(index, word)| match word {
+                "null" => Ok(None),
+                word => word
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| ParseError::InvalidKey(index)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Tree::from_level_order(keys)
+    }
+
+    /// Builds a tree from a parent array: the node at position `i` has the key `keys[i]`
+    /// and the parent `parents[i]`, which is `None` for the root only. The children of a
+    /// node are attached in the order of their positions, the first one as left child.
+    /// The ids of the nodes are assigned in level-order, so they match the positions
+    /// whenever the input is itself listed in level-order.
+    pub fn from_parent_array(keys: Vec<K>, parents: &[Option<usize>]) -> Result<Self, ParseError> {
+        if keys.len() != parents.len() {
+            return Err(ParseError::LengthMismatch);
+        }
+
+        let mut children = vec![Vec::new(); parents.len()];
+        let mut root = None;
+        for (index, parent) in parents.iter().enumerate() {
+            match *parent {
+                None if root.is_some() => return Err(ParseError::MultipleRoots),
+                None => root = Some(index),
+                Some(parent) if parent >= parents.len() => {
+                    return Err(ParseError::UnknownParent(index))
+                }
+                Some(parent) if children[parent].len() == 2 => {
+                    return Err(ParseError::TooManyChildren(parent))
+                }
+                Some(parent) => children[parent].push(index),
+            }
+        }
+        let root = match root {
+            Some(root) => root,
+            None if keys.is_empty() => return Ok(Tree::empty()),
+            None => return Err(ParseError::MissingRoot),
+        };
+
+        let mut keys: Vec<_> = keys.into_iter().map(Some).collect();
+        let mut tree = Tree::with_root(keys[root].take().unwrap());
+        let mut queue = VecDeque::from([(root, Tree::ROOT_NODE)]);
+        while let Some((index, node_id)) = queue.pop_front() {
+            for (&child, is_left) in children[index].iter().zip([true, false]) {
+                let child_id = tree.add_node(node_id, keys[child].take().unwrap(), is_left);
+                queue.push_back((child, child_id));
+            }
+        }
+
+        // nodes that were not reached hang off a cycle instead of the root
+        match keys.iter().position(Option::is_some) {
+            Some(index) => Err(ParseError::Unreachable(index)),
+            None => Ok(tree),
+        }
+    }
+
+    /// Builds a tree from a root key and a list of `(parent_id, key, is_left)` edges.
+    /// The edges are applied in order as calls to [`Tree::add_node`], so the `i`-th edge
+    /// creates the node with id `i + 1` and may only refer to nodes created before it.
+    pub fn from_edges(
+        root: K,
+        edges: impl IntoIterator<Item = (usize, K, bool)>,
+    ) -> Result<Self, ParseError> {
+        let mut tree = Tree::with_root(root);
+        for (index, (parent_id, key, is_left)) in edges.into_iter().enumerate() {
+            tree.try_add_node(parent_id, key, is_left)
+                .map_err(|err| ParseError::InvalidEdge(index, err))?;
+        }
+
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TreeError;
+
+    #[test]
+    fn test_from_level_order() {
+        let tree =
+            Tree::from_level_order([Some(10), Some(5), Some(22), None, Some(7), Some(20)]).unwrap();
+
+        assert_eq!(
+            tree.level_order(Tree::ROOT_NODE).collect::<Vec<_>>(),
+            vec![(0, &10), (1, &5), (2, &22), (3, &7), (4, &20)]
+        );
+        assert_eq!(
+            tree.in_order(Tree::ROOT_NODE)
+                .map(|(_, key)| *key)
+                .collect::<Vec<_>>(),
+            vec![5, 7, 10, 20, 22]
+        );
+
+        let tree = Tree::<u32>::from_level_order([]).unwrap();
+        assert!(tree.is_empty());
+
+        assert_eq!(
+            Tree::from_level_order([None, Some(1)]).unwrap_err(),
+            ParseError::MissingRoot
+        );
+        assert_eq!(
+            Tree::from_level_order([Some(1), None, None, None, Some(2)]).unwrap_err(),
+            ParseError::Orphan(4)
+        );
+    }
+
+    #[test]
+    fn test_parse_level_order() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        assert_eq!(tree.sum(), 64);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+
+        // the first tree of `test_is_balanced` that is not balanced
+        let tree: Tree<u32> =
+            Tree::parse_level_order("10, 5, 22, null, null, 22, null, 22").unwrap();
+        assert!(!tree.is_balanced(Tree::ROOT_NODE));
+
+        let tree: Tree<i32> = Tree::parse_level_order(" [ ] ").unwrap();
+        assert!(tree.is_empty());
+
+        assert_eq!(
+            Tree::<u32>::parse_level_order("[1,x]").unwrap_err(),
+            ParseError::InvalidKey(1)
+        );
+    }
+
+    #[test]
+    fn test_from_parent_array() {
+        let tree = Tree::from_parent_array(
+            vec![10, 5, 22, 7, 20],
+            &[None, Some(0), Some(0), Some(1), Some(2)],
+        )
+        .unwrap();
+        assert_eq!(
+            tree.pre_order(Tree::ROOT_NODE).collect::<Vec<_>>(),
+            vec![(0, &10), (1, &5), (3, &7), (2, &22), (4, &20)]
+        );
+
+        // the root does not have to come first
+        let tree = Tree::from_parent_array(vec![5, 10], &[Some(1), None]).unwrap();
+        assert_eq!(*tree.get(Tree::ROOT_NODE), 10);
+        assert_eq!(*tree.get(1), 5);
+
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2], &[None]).unwrap_err(),
+            ParseError::LengthMismatch
+        );
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2], &[None, None]).unwrap_err(),
+            ParseError::MultipleRoots
+        );
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2, 3, 4], &[None, Some(0), Some(0), Some(0)])
+                .unwrap_err(),
+            ParseError::TooManyChildren(0)
+        );
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2], &[None, Some(2)]).unwrap_err(),
+            ParseError::UnknownParent(1)
+        );
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2, 3], &[None, Some(2), Some(1)]).unwrap_err(),
+            ParseError::Unreachable(1)
+        );
+        assert_eq!(
+            Tree::from_parent_array(vec![1, 2], &[Some(1), Some(0)]).unwrap_err(),
+            ParseError::MissingRoot
+        );
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let tree = Tree::from_edges(
+            10,
+            [(0, 5, true), (0, 22, false), (1, 7, false), (2, 20, true)],
+        )
+        .unwrap();
+        assert_eq!(tree.sum(), 64);
+        assert_eq!(*tree.get(3), 7);
+
+        assert_eq!(
+            Tree::from_edges(10, [(0, 5, true), (0, 6, true)]).unwrap_err(),
+            ParseError::InvalidEdge(
+                1,
+                TreeError::SlotOccupied {
+                    parent_id: 0,
+                    is_left: true
+                }
+            )
+        );
+        assert_eq!(
+            Tree::from_edges(10, [(1, 5, true)]).unwrap_err(),
+            ParseError::InvalidEdge(0, TreeError::UnknownParent(1))
+        );
+    }
+}
@@ -0,0 +1,282 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * randomized tests comparing the checks of `Tree` with brute-force oracles
+ */
+
+use std::collections::VecDeque;
+
+use crate::generate::Rng;
+use crate::Tree;
+
+// Trees are generated, printed and shrunk in their level-order listing
+type Listing = Vec<Option<u32>>;
+
+fn parse(listing: &[Option<u32>]) -> Option<Tree<u32>> {
+    Tree::from_level_order(listing.iter().copied())
+        .ok()
+        .filter(|tree| !tree.is_empty())
+}
+
+fn to_listing(tree: &Tree<u32>) -> Listing {
+    let mut listing = Vec::new();
+    let mut queue = VecDeque::from([Some(Tree::ROOT_NODE)]);
+    while let Some(id) = queue.pop_front() {
+        match tree.get_node(id) {
+            Some(node) => {
+                listing.push(Some(node.key));
+                queue.push_back(node.id_left);
+                queue.push_back(node.id_right);
+            }
+            None => listing.push(None),
+        }
+    }
+    while listing.last() == Some(&None) {
+        listing.pop();
+    }
+
+    listing
+}
+
+// A random shape with up to `max_size` nodes. Complete shapes are generated on purpose,
+// as a random shape is almost never complete. The keys are random, increasing in
+// in-order or decreasing in level-order, so that some of the trees are BSTs or heaps.
+fn random_listing(rng: &mut Rng, max_size: u64) -> Listing {
+    let size = 1 + rng.below(max_size) as usize;
+    let complete = rng.below(3) == 0;
+
+    let mut tree = Tree::with_root(0);
+    let mut free_slots = vec![(Tree::ROOT_NODE, true), (Tree::ROOT_NODE, false)];
+    for _ in 1..size {
+        let slot = if complete {
+            0
+        } else {
+            rng.below(free_slots.len() as u64) as usize
+        };
+        let (parent_id, is_left) = free_slots.remove(slot);
+        let child_id = tree.add_node(parent_id, 0, is_left);
+        free_slots.push((child_id, true));
+        free_slots.push((child_id, false));
+    }
+
+    let order: Vec<usize> = match rng.below(3) {
+        0 => tree.in_order(Tree::ROOT_NODE).map(|(id, _)| id).collect(),
+        1 => {
+            let mut order: Vec<usize> = tree
+                .level_order(Tree::ROOT_NODE)
+                .map(|(id, _)| id)
+                .collect();
+            order.reverse();
+            order
+        }
+        _ => Vec::new(),
+    };
+    for id in 0..size {
+        tree.nodes[id].as_mut().unwrap().key = rng.below(8) as u32;
+    }
+    for (position, id) in order.into_iter().enumerate() {
+        // an occasional repeated key breaks the strict ordering of a BST
+        let key = position as u32 * 2 + u32::from(rng.below(8) == 0);
+        tree.nodes[id].as_mut().unwrap().key = key;
+    }
+
+    to_listing(&tree)
+}
+
+fn oracle_is_bst(tree: &Tree<u32>, node_id: usize) -> bool {
+    let keys: Vec<u32> = tree.in_order(node_id).map(|(_, key)| *key).collect();
+    keys.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+fn oracle_is_balanced(tree: &Tree<u32>, node_id: usize) -> bool {
+    let height = |id: Option<usize>| id.map_or(0, |id| oracle_height(tree, id));
+    tree.pre_order(node_id).all(|(id, _)| {
+        let node = tree.get_node(Some(id)).unwrap();
+        height(node.id_left).abs_diff(height(node.id_right)) <= 1
+    })
+}
+
+// The number of levels below `node_id`, found by a breadth-first search
+fn oracle_height(tree: &Tree<u32>, node_id: usize) -> usize {
+    let mut level = vec![Some(node_id)];
+    let mut height = 0;
+    while !level.is_empty() {
+        height += 1;
+        level = level
+            .into_iter()
+            .flat_map(|id| {
+                let node = tree.get_node(id).unwrap();
+                [node.id_left, node.id_right]
+            })
+            .filter(Option::is_some)
+            .collect();
+    }
+
+    height
+}
+
+fn oracle_is_max_heap(tree: &Tree<u32>, node_id: usize) -> bool {
+    // complete: no node comes after an empty position in level-order
+    let mut queue = VecDeque::from([Some(node_id)]);
+    let mut gap = false;
+    while let Some(id) = queue.pop_front() {
+        match tree.get_node(id) {
+            Some(_) if gap => return false,
+            Some(node) => {
+                queue.push_back(node.id_left);
+                queue.push_back(node.id_right);
+            }
+            None => gap = true,
+        }
+    }
+
+    tree.pre_order(node_id).all(|(id, key)| {
+        let node = tree.get_node(Some(id)).unwrap();
+        [node.id_left, node.id_right]
+            .into_iter()
+            .flatten()
+            .all(|child| tree.get(child) <= key)
+    })
+}
+
+// Returns the name of the first check of some node that disagrees with its oracle
+fn disagreement(listing: &[Option<u32>]) -> Option<String> {
+    let tree = parse(listing)?;
+
+    for (id, _) in tree.pre_order(Tree::ROOT_NODE) {
+        let checks = [
+            ("is_bst", tree.is_bst(id), oracle_is_bst(&tree, id)),
+            (
+                "check_bst",
+                tree.check_bst(id).is_ok(),
+                oracle_is_bst(&tree, id),
+            ),
+            (
+                "is_balanced",
+                tree.is_balanced(id),
+                oracle_is_balanced(&tree, id),
+            ),
+            (
+                "check_balanced",
+                tree.check_balanced(id).is_ok(),
+                oracle_is_balanced(&tree, id),
+            ),
+            (
+                "is_max_heap",
+                tree.is_max_heap(id),
+                oracle_is_max_heap(&tree, id),
+            ),
+            (
+                "check_max_heap",
+                tree.check_max_heap(id).is_ok(),
+                oracle_is_max_heap(&tree, id),
+            ),
+        ];
+        for (name, actual, expected) in checks {
+            if actual != expected {
+                return Some(format!("{name}({id}) is {actual}, expected {expected}"));
+            }
+        }
+    }
+
+    None
+}
+
+// Smaller variants of a listing: shorter, with a subtree cut off, or with a smaller key
+fn shrink_candidates(listing: &[Option<u32>]) -> Vec<Listing> {
+    let mut candidates = Vec::new();
+    for i in (1..listing.len()).rev() {
+        candidates.push(listing[..i].to_vec());
+    }
+    for i in 1..listing.len() {
+        let mut shorter = listing.to_vec();
+        shorter.remove(i);
+        candidates.push(shorter);
+        if listing[i].is_some() {
+            let mut cut = listing.to_vec();
+            cut[i] = None;
+            candidates.push(cut);
+        }
+    }
+    for i in 0..listing.len() {
+        if let Some(key) = listing[i].filter(|&key| key > 0) {
+            for smaller in [0, key / 2, key - 1] {
+                let mut candidate = listing.to_vec();
+                candidate[i] = Some(smaller);
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    candidates
+}
+
+// Greedily replaces a failing listing by a smaller failing one, until no candidate fails
+fn shrink(mut listing: Listing, fails: impl Fn(&[Option<u32>]) -> bool) -> Listing {
+    while let Some(smaller) = shrink_candidates(&listing)
+        .into_iter()
+        .find(|candidate| fails(candidate))
+    {
+        listing = smaller;
+    }
+
+    listing
+}
+
+#[test]
+fn test_checks_match_oracles() {
+    let mut rng = Rng::new(0x2545f4914f6cdd1d);
+
+    for _ in 0..3000 {
+        let listing = random_listing(&mut rng, 24);
+        if disagreement(&listing).is_some() {
+            let minimal = shrink(listing, |listing| disagreement(listing).is_some());
+            let tree = parse(&minimal).unwrap();
+            panic!(
+                "{}\nminimal failing tree {:?}:\n{}",
+                disagreement(&minimal).unwrap(),
+                minimal,
+                tree
+            );
+        }
+    }
+}
+
+#[test]
+fn test_generator_variety() {
+    let mut rng = Rng::new(0x9e3779b97f4a7c15);
+    let trees: Vec<Tree<u32>> = (0..500)
+        .map(|_| parse(&random_listing(&mut rng, 24)).unwrap())
+        .collect();
+
+    // the interesting answers of every check are produced often enough
+    let count = |check: fn(&Tree<u32>) -> bool| trees.iter().filter(|tree| check(tree)).count();
+    for check in [
+        |tree: &Tree<u32>| tree.is_bst(Tree::ROOT_NODE),
+        |tree: &Tree<u32>| !tree.is_bst(Tree::ROOT_NODE),
+        |tree: &Tree<u32>| tree.is_balanced(Tree::ROOT_NODE),
+        |tree: &Tree<u32>| !tree.is_balanced(Tree::ROOT_NODE),
+        |tree: &Tree<u32>| tree.is_max_heap(Tree::ROOT_NODE),
+        |tree: &Tree<u32>| !tree.is_max_heap(Tree::ROOT_NODE),
+    ] {
+        assert!(count(check) >= 25);
+    }
+}
+
+#[test]
+fn test_shrink() {
+    // the checks agree with the oracles, so the shrinker is exercised on
+    // a property that fails for any tree holding the key 3
+    let fails = |listing: &[Option<u32>]| {
+        parse(listing).is_some_and(|tree| tree.count(Tree::ROOT_NODE, |key| *key == 3) > 0)
+    };
+    let listing = shrink(
+        vec![Some(9), Some(4), Some(7), None, Some(3), Some(1), Some(8)],
+        fails,
+    );
+
+    // the root cannot be cut off, only its key gets smaller
+    assert_eq!(listing, vec![Some(0), Some(3)]);
+}
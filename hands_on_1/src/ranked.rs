@@ -0,0 +1,330 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * order-statistic queries on a binary search tree that tracks subtree sizes
+ */
+
+use crate::{Bst, Tree};
+
+/// A binary search tree stored in a `Tree` that also records the size of the subtree
+/// rooted at every node, so that keys can be selected by rank and counted by range
+/// in O(height).
+///
+/// Ranks start from 0: the key of rank `r` has exactly `r` smaller keys in the tree,
+/// and it is the `r + 1`-th smallest key.
+#[derive(Debug, Clone)]
+pub struct RankedBst<K> {
+    bst: Bst<K>,
+    // number of nodes of the subtree rooted at every id
+    sizes: Vec<usize>,
+}
+
+/// Iterator over the keys of a [`RankedBst`] inside a range, in increasing order.
+/// Subtrees entirely outside the range are never visited.
+pub struct KeyRange<'a, K> {
+    tree: &'a Tree<K>,
+    low: &'a K,
+    high: &'a K,
+    stack: Vec<usize>,
+}
+
+impl<K: Ord> Default for RankedBst<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> RankedBst<K> {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self {
+            bst: Bst::new(),
+            sizes: Vec::new(),
+        }
+    }
+
+    /// Wraps a tree that already is a binary search tree, computing its subtree sizes.
+    /// The tree is handed back if it does not satisfy the BST property.
+    pub fn from_tree(tree: Tree<K>) -> Result<Self, Tree<K>> {
+        let bst = Bst::from_tree(tree)?;
+        let mut sizes = vec![0; bst.tree.nodes.len()];
+        for (id, _) in bst.tree.post_order(Tree::ROOT_NODE) {
+            let node = bst.tree.get_node(Some(id)).unwrap();
+            sizes[id] = 1 + [node.id_left, node.id_right]
+                .into_iter()
+                .flatten()
+                .map(|child| sizes[child])
+                .sum::<usize>();
+        }
+
+        Ok(Self { bst, sizes })
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &Tree<K> {
+        self.bst.tree()
+    }
+
+    /// Unwraps the underlying tree.
+    pub fn into_tree(self) -> Tree<K> {
+        self.bst.into_tree()
+    }
+
+    /// Returns the number of keys in the tree.
+    pub fn len(&self) -> usize {
+        self.bst.len()
+    }
+
+    /// Returns `true` if the tree holds no key.
+    pub fn is_empty(&self) -> bool {
+        self.bst.is_empty()
+    }
+
+    /// Inserts `key`. Returns `false` if the key was already present,
+    /// in which case the tree is unchanged.
+    pub fn insert(&mut self, key: K) -> bool {
+        let mut path = Vec::new();
+        let Some(leaf) = self.bst.insert_by(key, |node_id| path.push(node_id)) else {
+            return false;
+        };
+
+        if self.sizes.len() <= leaf {
+            self.sizes.resize(leaf + 1, 0);
+        }
+        self.sizes[leaf] = 1;
+        for node_id in path {
+            self.sizes[node_id] += 1;
+        }
+
+        true
+    }
+
+    /// Removes `key`. Returns `false` if the key was not present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let mut path = Vec::new();
+        if !self.bst.remove_by(key, |node_id| path.push(node_id)) {
+            return false;
+        }
+
+        for node_id in path {
+            self.sizes[node_id] -= 1;
+        }
+
+        true
+    }
+
+    /// Returns `true` if the tree holds `key`.
+    pub fn contains(&self, key: &K) -> bool {
+        self.bst.contains(key)
+    }
+
+    /// Returns the `k`-th smallest key, counting from 1, i.e. the key of rank `k - 1`.
+    /// Returns `None` if `k` is 0 or the tree holds fewer than `k` keys.
+    pub fn kth_smallest(&self, k: usize) -> Option<&K> {
+        // the number of keys still to skip
+        let mut k = k.checked_sub(1)?;
+        let tree = self.tree();
+        let mut node_id = tree.subtree(Tree::ROOT_NODE);
+
+        while let Some(node) = tree.get_node(node_id) {
+            let left_size = self.size_of(node.id_left);
+            if k < left_size {
+                node_id = node.id_left;
+            } else if k == left_size {
+                return Some(&node.key);
+            } else {
+                k -= left_size + 1;
+                node_id = node.id_right;
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of keys of the tree that are smaller than `key`.
+    /// `key` itself does not need to be in the tree.
+    pub fn rank(&self, key: &K) -> usize {
+        self.count_below(key, false)
+    }
+
+    /// Returns the number of keys of the tree inside `[low, high]`, bounds included.
+    pub fn count_in_range(&self, low: &K, high: &K) -> usize {
+        if low > high {
+            return 0;
+        }
+
+        self.count_below(high, true) - self.count_below(low, false)
+    }
+
+    /// Iterates, in increasing order, over the ids and the keys inside `[low, high]`.
+    pub fn range<'a>(&'a self, low: &'a K, high: &'a K) -> KeyRange<'a, K> {
+        let tree = self.tree();
+        let mut range = KeyRange {
+            tree,
+            low,
+            high,
+            stack: Vec::new(),
+        };
+        range.push_left(tree.subtree(Tree::ROOT_NODE));
+
+        range
+    }
+
+    // Counts the keys smaller than `key`, or not greater than it if `inclusive`
+    fn count_below(&self, key: &K, inclusive: bool) -> usize {
+        let tree = self.tree();
+        let mut node_id = tree.subtree(Tree::ROOT_NODE);
+        let mut count = 0;
+
+        while let Some(node) = tree.get_node(node_id) {
+            if node.key < *key || (inclusive && node.key == *key) {
+                count += self.size_of(node.id_left) + 1;
+                node_id = node.id_right;
+            } else {
+                node_id = node.id_left;
+            }
+        }
+
+        count
+    }
+
+    fn size_of(&self, node_id: Option<usize>) -> usize {
+        node_id.map_or(0, |id| self.sizes[id])
+    }
+}
+
+impl<K: Ord> KeyRange<'_, K> {
+    // Pushes the path from `node_id` down to its smallest key not below `low`,
+    // skipping the nodes that are below `low` together with their left subtrees
+    fn push_left(&mut self, mut node_id: Option<usize>) {
+        while let Some(node) = self.tree.get_node(node_id) {
+            if node.key < *self.low {
+                node_id = node.id_right;
+            } else {
+                self.stack.push(node_id.unwrap());
+                node_id = node.id_left;
+            }
+        }
+    }
+}
+
+impl<'a, K: Ord> Iterator for KeyRange<'a, K> {
+    type Item = (usize, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node_id = self.stack.pop()?;
+        let node = self.tree.get_node(Some(node_id)).unwrap();
+        if node.key > *self.high {
+            // every key left on the stack is even greater
+            self.stack.clear();
+            return None;
+        }
+        self.push_left(node.id_right);
+
+        Some((node_id, &node.key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    #[test]
+    fn test_order_statistics() {
+        let mut ranked = RankedBst::new();
+        for key in [50, 30, 70, 20, 40, 60, 80, 35] {
+            ranked.insert(key);
+        }
+
+        assert_eq!(ranked.kth_smallest(0), None);
+        assert_eq!(ranked.kth_smallest(1), Some(&20));
+        assert_eq!(ranked.kth_smallest(4), Some(&40));
+        assert_eq!(ranked.kth_smallest(8), Some(&80));
+        assert_eq!(ranked.kth_smallest(9), None);
+
+        assert_eq!(ranked.rank(&20), 0);
+        assert_eq!(ranked.rank(&50), 4);
+        assert_eq!(ranked.rank(&55), 5);
+        assert_eq!(ranked.rank(&100), 8);
+
+        assert_eq!(ranked.count_in_range(&30, &60), 5);
+        assert_eq!(ranked.count_in_range(&31, &39), 1);
+        assert_eq!(ranked.count_in_range(&60, &30), 0);
+
+        let keys: Vec<u32> = ranked.range(&33, &70).map(|(_, key)| *key).collect();
+        assert_eq!(keys, vec![35, 40, 50, 60, 70]);
+        assert_eq!(ranked.range(&81, &90).next(), None);
+
+        assert!(ranked.remove(&50));
+        assert_eq!(ranked.kth_smallest(5), Some(&60));
+        assert_eq!(ranked.rank(&60), 4);
+    }
+
+    #[test]
+    fn test_from_tree() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        let ranked = RankedBst::from_tree(tree).unwrap();
+        assert_eq!(ranked.kth_smallest(3), Some(&10));
+        assert_eq!(ranked.count_in_range(&6, &21), 3);
+
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,12]").unwrap();
+        assert!(RankedBst::from_tree(tree).is_err());
+    }
+
+    #[test]
+    fn test_range_skips_subtrees() {
+        let mut ranked = RankedBst::new();
+        for key in 0..1000 {
+            ranked.insert(key);
+        }
+
+        // a degenerate tree: the range starts deep down and stops right after
+        let range = ranked.range(&990, &992);
+        let keys: Vec<u32> = range.map(|(_, key)| *key).collect();
+        assert_eq!(keys, vec![990, 991, 992]);
+        let mut range = ranked.range(&10, &12);
+        range.next();
+        assert_eq!(range.stack.len(), 1);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng = Rng::new(5);
+        let mut next = || rng.below(500) as u32;
+
+        let mut ranked = RankedBst::new();
+        let mut expected = std::collections::BTreeSet::new();
+        for _ in 0..3000 {
+            let key = next();
+            if next() % 3 == 0 {
+                assert_eq!(ranked.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(ranked.insert(key), expected.insert(key));
+            }
+
+            let (low, high) = (next(), next());
+            assert_eq!(ranked.rank(&low), expected.range(..low).count());
+            let k = high as usize % (expected.len() + 2);
+            assert_eq!(
+                ranked.kth_smallest(k),
+                k.checked_sub(1).and_then(|rank| expected.iter().nth(rank))
+            );
+            if low <= high {
+                assert_eq!(
+                    ranked.count_in_range(&low, &high),
+                    expected.range(low..=high).count()
+                );
+                assert_eq!(
+                    ranked
+                        .range(&low, &high)
+                        .map(|(_, key)| *key)
+                        .collect::<Vec<_>>(),
+                    expected.range(low..=high).copied().collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+}
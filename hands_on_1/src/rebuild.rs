@@ -0,0 +1,283 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * constructors rebuilding a `Tree` from the keys of its traversals
+ */
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::{ParseError, Tree};
+
+impl<K: Clone> Tree<K> {
+    /// Rebuilds the unique tree with the given pre-order and in-order sequences of keys.
+    /// The keys must be distinct, as repeated keys can make the tree ambiguous; the first
+    /// repetition is reported as `ParseError::DuplicateKey`. Empty sequences give an
+    /// empty tree. The ids of the nodes are assigned in pre-order.
+    pub fn from_pre_in_order(preorder: &[K], inorder: &[K]) -> Result<Self, ParseError>
+    where
+        K: Eq + Hash,
+    {
+        check_traversals(preorder, inorder)?;
+        let tree = Tree::from_traversals(preorder.iter(), inorder.iter(), true)?;
+
+        let same_pre_order = tree
+            .pre_order(Tree::ROOT_NODE)
+            .map(|(_, key)| key)
+            .eq(preorder);
+        let same_in_order = tree
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| key)
+            .eq(inorder);
+        if same_pre_order && same_in_order {
+            Ok(tree)
+        } else {
+            Err(ParseError::Inconsistent)
+        }
+    }
+
+    /// Same as [`Tree::from_pre_in_order`], from the post-order and in-order sequences.
+    /// The ids of the nodes are assigned in reversed post-order.
+    pub fn from_post_in_order(postorder: &[K], inorder: &[K]) -> Result<Self, ParseError>
+    where
+        K: Eq + Hash,
+    {
+        check_traversals(postorder, inorder)?;
+        // the reversed post-order is a pre-order that visits the right subtree first
+        let tree = Tree::from_traversals(postorder.iter().rev(), inorder.iter().rev(), false)?;
+
+        let same_post_order = tree
+            .post_order(Tree::ROOT_NODE)
+            .map(|(_, key)| key)
+            .eq(postorder);
+        let same_in_order = tree
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| key)
+            .eq(inorder);
+        if same_post_order && same_in_order {
+            Ok(tree)
+        } else {
+            Err(ParseError::Inconsistent)
+        }
+    }
+
+    /// Rebuilds the unique binary search tree with the given pre-order sequence of keys.
+    /// Returns `ParseError::DuplicateKey` for a repeated key and `ParseError::Inconsistent`
+    /// if no BST has this pre-order. The ids of the nodes are assigned in pre-order.
+    pub fn from_bst_pre_order(preorder: &[K]) -> Result<Self, ParseError>
+    where
+        K: Ord,
+    {
+        let Some((first, rest)) = preorder.split_first() else {
+            return Ok(Tree::empty());
+        };
+        let mut tree = Tree::with_root(first.clone());
+
+        // The stack holds the nodes still waiting for a right child: exactly the keys seen
+        // so far that are greater than `lower`, decreasing from the bottom. `lower` is the
+        // last node that got a right child, all the following keys must be greater than it.
+        let mut stack = vec![Tree::ROOT_NODE];
+        let mut lower: Option<usize> = None;
+        for (index, key) in rest.iter().enumerate() {
+            let index = index + 1;
+            if let Some(lower) = lower {
+                match key.cmp(tree.get(lower)) {
+                    Ordering::Less => return Err(ParseError::Inconsistent),
+                    Ordering::Equal => return Err(ParseError::DuplicateKey(index)),
+                    Ordering::Greater => {}
+                }
+            }
+
+            let mut closed = None;
+            while let Some(&top) = stack.last() {
+                if tree.get(top) == key {
+                    return Err(ParseError::DuplicateKey(index));
+                }
+                if tree.get(top) > key {
+                    break;
+                }
+                closed = stack.pop();
+            }
+
+            let child_id = match closed {
+                Some(parent_id) => {
+                    lower = Some(parent_id);
+                    tree.add_node(parent_id, key.clone(), false)
+                }
+                None => tree.add_node(*stack.last().unwrap(), key.clone(), true),
+            };
+            stack.push(child_id);
+        }
+
+        Ok(tree)
+    }
+
+    // Builds a tree from `order`, which lists every node before its descendants, and from
+    // `inorder`, read in the matching direction. With `first_is_left`, `order` visits left
+    // subtrees first, as a pre-order does; otherwise everything is mirrored.
+    //
+    // A node becomes the first child of the node on top of the stack, until `inorder`
+    // reaches that node: the nodes it reaches are closed, and the next node becomes the
+    // other child of the last one closed. The result must be verified by the caller.
+    fn from_traversals<'a>(
+        mut order: impl Iterator<Item = &'a K>,
+        inorder: impl Iterator<Item = &'a K>,
+        first_is_left: bool,
+    ) -> Result<Self, ParseError>
+    where
+        K: PartialEq + 'a,
+    {
+        let Some(first) = order.next() else {
+            return Ok(Tree::empty());
+        };
+        let inorder: Vec<&K> = inorder.collect();
+        let mut tree = Tree::with_root(first.clone());
+        let mut stack = vec![Tree::ROOT_NODE];
+        let mut position = 0;
+
+        for key in order {
+            let mut parent = (*stack.last().unwrap(), first_is_left);
+            while let Some(&top) = stack.last() {
+                if inorder.get(position) != Some(&tree.get(top)) {
+                    break;
+                }
+                parent = (stack.pop().unwrap(), !first_is_left);
+                position += 1;
+            }
+
+            let (parent_id, is_left) = parent;
+            let child_id = tree
+                .try_add_node(parent_id, key.clone(), is_left)
+                .map_err(|_| ParseError::Inconsistent)?;
+            stack.push(child_id);
+        }
+
+        Ok(tree)
+    }
+}
+
+// Rejects sequences of different lengths or with repeated keys
+fn check_traversals<K: Eq + Hash>(order: &[K], inorder: &[K]) -> Result<(), ParseError> {
+    if order.len() != inorder.len() {
+        return Err(ParseError::LengthMismatch);
+    }
+    for keys in [order, inorder] {
+        let mut seen = HashSet::new();
+        if let Some(index) = keys.iter().position(|key| !seen.insert(key)) {
+            return Err(ParseError::DuplicateKey(index));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pre_in_order() {
+        let expected: Tree<u32> = Tree::parse_level_order("[3,9,20,null,null,15,7]").unwrap();
+        let tree = Tree::from_pre_in_order(&[3, 9, 20, 15, 7], &[9, 3, 15, 20, 7]).unwrap();
+        tree.assert_links();
+        assert_eq!(tree, expected);
+
+        // a chain, where every node is a right child
+        let tree = Tree::from_pre_in_order(&[1, 2, 3], &[1, 2, 3]).unwrap();
+        assert_eq!(tree, Tree::parse_level_order("[1,null,2,null,3]").unwrap());
+
+        let tree = Tree::from_pre_in_order(&["b", "a", "c"], &["a", "b", "c"]).unwrap();
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+
+        let tree = Tree::<u32>::from_pre_in_order(&[], &[]).unwrap();
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_from_post_in_order() {
+        let expected: Tree<u32> = Tree::parse_level_order("[3,9,20,null,null,15,7]").unwrap();
+        let tree = Tree::from_post_in_order(&[9, 15, 7, 20, 3], &[9, 3, 15, 20, 7]).unwrap();
+        tree.assert_links();
+        assert_eq!(tree, expected);
+
+        let tree = Tree::from_post_in_order(&[3, 2, 1], &[3, 2, 1]).unwrap();
+        assert_eq!(tree, Tree::parse_level_order("[1,2,null,3]").unwrap());
+    }
+
+    #[test]
+    fn test_traversals_round_trip() {
+        let mut generator = crate::Generator::new(5);
+        for size in [1, 2, 10, 200] {
+            let tree = generator.uniform(size, true);
+            let pre: Vec<u32> = tree
+                .pre_order(Tree::ROOT_NODE)
+                .map(|(_, key)| *key)
+                .collect();
+            let post: Vec<u32> = tree
+                .post_order(Tree::ROOT_NODE)
+                .map(|(_, key)| *key)
+                .collect();
+            let inorder: Vec<u32> = tree
+                .in_order(Tree::ROOT_NODE)
+                .map(|(_, key)| *key)
+                .collect();
+
+            assert_eq!(Tree::from_pre_in_order(&pre, &inorder).unwrap(), tree);
+            assert_eq!(Tree::from_post_in_order(&post, &inorder).unwrap(), tree);
+            assert_eq!(Tree::from_bst_pre_order(&pre).unwrap(), tree);
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_traversals() {
+        assert_eq!(
+            Tree::from_pre_in_order(&[1, 2], &[1, 2, 3]).unwrap_err(),
+            ParseError::LengthMismatch
+        );
+        assert_eq!(
+            Tree::from_pre_in_order(&[1, 2, 1], &[1, 2, 1]).unwrap_err(),
+            ParseError::DuplicateKey(2)
+        );
+        assert_eq!(
+            Tree::from_pre_in_order(&[1, 2, 3], &[1, 2, 4]).unwrap_err(),
+            ParseError::Inconsistent
+        );
+        // the same keys, but no tree has both sequences
+        assert_eq!(
+            Tree::from_pre_in_order(&[1, 2, 3], &[3, 1, 2]).unwrap_err(),
+            ParseError::Inconsistent
+        );
+        assert_eq!(
+            Tree::from_post_in_order(&[1, 2, 3], &[2, 3, 1]).unwrap_err(),
+            ParseError::Inconsistent
+        );
+    }
+
+    #[test]
+    fn test_from_bst_pre_order() {
+        let tree = Tree::from_bst_pre_order(&[8, 5, 1, 7, 10, 12]).unwrap();
+        tree.assert_links();
+        assert_eq!(
+            tree,
+            Tree::parse_level_order("[8,5,10,1,7,null,12]").unwrap()
+        );
+
+        assert_eq!(
+            Tree::from_bst_pre_order(&[8, 5, 1, 7, 5]).unwrap_err(),
+            ParseError::DuplicateKey(4)
+        );
+        assert_eq!(
+            Tree::from_bst_pre_order(&[8, 5, 8]).unwrap_err(),
+            ParseError::DuplicateKey(2)
+        );
+        // 4 comes after the right subtree of 5 was started, but is smaller than 5
+        assert_eq!(
+            Tree::from_bst_pre_order(&[8, 5, 7, 4]).unwrap_err(),
+            ParseError::Inconsistent
+        );
+        assert!(Tree::<u32>::from_bst_pre_order(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * removal of nodes and compaction of the arena of a `Tree`
+ */
+
+use std::collections::VecDeque;
+
+use crate::{Node, Tree, TreeError};
+
+impl<K> Tree<K> {
+    /// Removes the leaf `node_id` and returns its key.
+    /// The id of the removed node may be reused by a later `add_node`; the ids of
+    /// all the other nodes do not change.
+    pub fn remove_leaf(&mut self, node_id: usize) -> Result<K, TreeError> {
+        let node = self.try_node(node_id)?;
+        if node.id_left.is_some() || node.id_right.is_some() {
+            return Err(TreeError::NotALeaf(node_id));
+        }
+
+        let mut detached = self.detach_subtree(node_id)?;
+        Ok(detached.nodes.pop().unwrap().unwrap().key)
+    }
+
+    /// Removes the whole subtree rooted at `node_id` and returns it as a new tree.
+    /// In the returned tree, `node_id` becomes `ROOT_NODE` and the remaining nodes are
+    /// numbered in level-order. Detaching the root leaves this tree empty.
+    pub fn detach_subtree(&mut self, node_id: usize) -> Result<Tree<K>, TreeError> {
+        self.try_node(node_id)?;
+
+        if let Some((parent_id, is_left)) = self.parent_link(node_id) {
+            self.set_child(parent_id, is_left, None);
+        }
+
+        let (nodes, old_ids) = self.take_subtree(node_id);
+        if node_id == Tree::ROOT_NODE {
+            self.nodes.clear();
+            self.free.clear();
+        } else {
+            self.free.extend(old_ids);
+        }
+
+        Ok(Tree {
+            nodes: nodes.into_iter().map(Some).collect(),
+            free: Vec::new(),
+        })
+    }
+
+    /// Replaces the left (if `is_left` is `true`) or right child of `parent_id` by a new
+    /// leaf with the given `key`. Returns the id of the new leaf and the subtree that
+    /// previously occupied the slot, if any.
+    pub fn replace_child(
+        &mut self,
+        parent_id: usize,
+        key: K,
+        is_left: bool,
+    ) -> Result<(usize, Option<Tree<K>>), TreeError> {
+        let parent = self
+            .try_node(parent_id)
+            .map_err(|_| TreeError::UnknownParent(parent_id))?;
+        let old_child = if is_left {
+            parent.id_left
+        } else {
+            parent.id_right
+        };
+
+        let detached = match old_child {
+            Some(old_child) => Some(self.detach_subtree(old_child)?),
+            None => None,
+        };
+        let child_id = self.try_add_node(parent_id, key, is_left)?;
+
+        Ok((child_id, detached))
+    }
+
+    /// Renumbers the nodes so that they occupy the ids `0..len()` in level-order,
+    /// dropping all the vacant slots left by removals.
+    /// Returns a map from the old ids to the new ones: `map[old_id]` is `None` for
+    /// the ids that did not refer to any node.
+    pub fn compact(&mut self) -> Vec<Option<usize>> {
+        let mut map = vec![None; self.nodes.len()];
+
+        if !self.is_empty() {
+            let (nodes, old_ids) = self.take_subtree(Tree::ROOT_NODE);
+            for (new_id, old_id) in old_ids.into_iter().enumerate() {
+                map[old_id] = Some(new_id);
+            }
+            self.nodes = nodes.into_iter().map(Some).collect();
+        }
+        self.free.clear();
+
+        map
+    }
+
+    // Moves the nodes of the subtree rooted at `node_id` out of the arena, leaving
+    // their slots vacant. The nodes are returned in level-order, with the links
+    // between them renumbered to their new positions, together with their old ids.
+    // The first node, the root of the subtree, loses the link to its parent.
+    fn take_subtree(&mut self, node_id: usize) -> (Vec<Node<K>>, Vec<usize>) {
+        let mut nodes = Vec::new();
+        let mut old_ids = Vec::new();
+        let mut queue = VecDeque::from([(node_id, None)]);
+
+        while let Some((old_id, parent)) = queue.pop_front() {
+            let mut node = self.nodes[old_id].take().unwrap();
+            let new_id = nodes.len();
+            node.id_parent = parent;
+
+            // every node queued before a child gets its new id before the child
+            for child in [&mut node.id_left, &mut node.id_right] {
+                if let Some(child_id) = *child {
+                    *child = Some(new_id + 1 + queue.len());
+                    queue.push_back((child_id, Some(new_id)));
+                }
+            }
+
+            nodes.push(node);
+            old_ids.push(old_id);
+        }
+
+        (nodes, old_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_leaf() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(tree.remove_leaf(1), Err(TreeError::NotALeaf(1)));
+        assert_eq!(tree.remove_leaf(4), Ok(7));
+        assert_eq!(tree.remove_leaf(4), Err(TreeError::NodeRemoved(4)));
+        assert_eq!(tree.try_get(4), Err(TreeError::NodeRemoved(4)));
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.sum(), 58);
+
+        // the vacant id is reused, the other ids are stable
+        assert_eq!(tree.add_node(5, 21, false), 4);
+        assert_eq!(*tree.get(5), 20);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_detach_subtree() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        let detached = tree.detach_subtree(1).unwrap();
+        assert_eq!(detached.len(), 3);
+        assert_eq!(
+            detached.level_order(Tree::ROOT_NODE).collect::<Vec<_>>(),
+            vec![(0, &5), (1, &1), (2, &7)]
+        );
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.sum(), 52);
+        assert!(!tree.is_balanced(Tree::ROOT_NODE));
+        assert_eq!(tree.try_get(3), Err(TreeError::NodeRemoved(3)));
+
+        let detached = tree.detach_subtree(Tree::ROOT_NODE).unwrap();
+        assert_eq!(detached.sum(), 52);
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.sum(), 0);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert_eq!(tree.in_order(Tree::ROOT_NODE).next(), None);
+    }
+
+    #[test]
+    fn test_replace_child() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        let (new_id, old) = tree.replace_child(Tree::ROOT_NODE, 30, false).unwrap();
+        assert_eq!(old.unwrap().sum(), 42);
+        assert_eq!(*tree.get(new_id), 30);
+        assert_eq!(tree.sum(), 53);
+
+        let (_, old) = tree.replace_child(4, 8, true).unwrap();
+        assert!(old.is_none());
+
+        assert_eq!(
+            tree.replace_child(9, 8, true).unwrap_err(),
+            TreeError::UnknownParent(9)
+        );
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+        tree.remove_leaf(3).unwrap();
+        tree.detach_subtree(2).unwrap();
+
+        let map = tree.compact();
+        assert_eq!(map, vec![Some(0), Some(1), None, None, Some(2), None]);
+        assert_eq!(
+            tree.pre_order(Tree::ROOT_NODE).collect::<Vec<_>>(),
+            vec![(0, &10), (1, &5), (2, &7)]
+        );
+
+        // no vacant slot is left to be reused
+        assert_eq!(tree.add_node(2, 6, true), 3);
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * text renderings of a `Tree`: an ASCII level diagram and Graphviz DOT
+ */
+
+use std::fmt::{self, Display, Write};
+
+use crate::Tree;
+
+/// Which nodes [`Tree::to_dot`] should highlight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// No node is highlighted.
+    Nothing,
+    /// Nodes whose left subtree holds a key not smaller than their own, or whose
+    /// right subtree holds a key not greater than their own.
+    BstViolations,
+    /// Nodes whose key is smaller than the key of one of their children.
+    MaxHeapViolations,
+}
+
+impl<K> Tree<K> {
+    /// Renders the whole tree in the Graphviz DOT language.
+    /// Every node is labelled with its id and key, every edge with the side of the child.
+    /// Nodes selected by `highlight` are filled in red.
+    pub fn to_dot(&self, highlight: Highlight) -> String
+    where
+        K: Display + PartialOrd,
+    {
+        let highlighted = match highlight {
+            Highlight::Nothing => vec![false; self.nodes.len()],
+            Highlight::BstViolations => self.bst_violations(),
+            Highlight::MaxHeapViolations => self.max_heap_violations(),
+        };
+
+        let mut dot = String::from("digraph tree {\n");
+        for (id, key) in self.pre_order(Tree::ROOT_NODE) {
+            let style = if highlighted[id] {
+                ", style=filled, fillcolor=red"
+            } else {
+                ""
+            };
+            // quotes and backslashes would end or escape the label
+            let key = key.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(dot, "    {id} [label=\"{id}: {key}\"{style}];").unwrap();
+
+            let node = self.get_node(Some(id)).unwrap();
+            for (child, side) in [(node.id_left, "L"), (node.id_right, "R")] {
+                if let Some(child) = child {
+                    writeln!(dot, "    {id} -> {child} [label=\"{side}\"];").unwrap();
+                }
+            }
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    // Marks, for every id, whether the node breaks the ordering of a BST
+    // with respect to the keys in its own subtrees.
+    fn bst_violations(&self) -> Vec<bool>
+    where
+        K: PartialOrd,
+    {
+        let mut violations = vec![false; self.nodes.len()];
+        // minimum and maximum key of every subtree visited so far
+        let mut bounds: Vec<Option<(&K, &K)>> = vec![None; self.nodes.len()];
+
+        for (id, key) in self.post_order(Tree::ROOT_NODE) {
+            let node = self.get_node(Some(id)).unwrap();
+            let left = node.id_left.and_then(|child| bounds[child]);
+            let right = node.id_right.and_then(|child| bounds[child]);
+
+            violations[id] =
+                left.is_some_and(|(_, max)| max >= key) || right.is_some_and(|(min, _)| min <= key);
+
+            // the subtrees are not necessarily ordered, so both of them are inspected
+            let (mut min, mut max) = (key, key);
+            for (child_min, child_max) in [left, right].into_iter().flatten() {
+                if child_min < min {
+                    min = child_min;
+                }
+                if child_max > max {
+                    max = child_max;
+                }
+            }
+            bounds[id] = Some((min, max));
+        }
+
+        violations
+    }
+
+    // Marks, for every id, whether the node is smaller than one of its children
+    fn max_heap_violations(&self) -> Vec<bool>
+    where
+        K: PartialOrd,
+    {
+        let mut violations = vec![false; self.nodes.len()];
+
+        for (id, key) in self.pre_order(Tree::ROOT_NODE) {
+            let node = self.get_node(Some(id)).unwrap();
+            violations[id] = [node.id_left, node.id_right]
+                .into_iter()
+                .flatten()
+                .any(|child| self.get(child) > key);
+        }
+
+        violations
+    }
+}
+
+/// Draws the tree as an ASCII diagram with one line of keys per level.
+/// Every key gets its own column, in in-order, and `/` or `\` link it to its parent:
+///
+/// ```text
+///       10
+///   /         \
+///  5          22
+///    \       /
+///     7    20
+/// ```
+impl<K: Display> Display for Tree<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<(usize, String)> = self
+            .in_order(Tree::ROOT_NODE)
+            .map(|(id, key)| (id, key.to_string()))
+            .collect();
+        // widths are counted in chars, as the padding of `write!` is
+        let width = keys
+            .iter()
+            .map(|(_, key)| key.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        // the column of every node, and its level
+        let mut column = vec![0; self.nodes.len()];
+        for (position, (id, _)) in keys.iter().enumerate() {
+            column[*id] = position;
+        }
+        let mut depth = vec![0; self.nodes.len()];
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+        for (id, _) in self.level_order(Tree::ROOT_NODE) {
+            if depth[id] == levels.len() {
+                levels.push(Vec::new());
+            }
+            levels[depth[id]].push(id);
+
+            let node = self.get_node(Some(id)).unwrap();
+            for child in [node.id_left, node.id_right].into_iter().flatten() {
+                depth[child] = depth[id] + 1;
+            }
+        }
+
+        let mut labels = vec![String::new(); self.nodes.len()];
+        for (id, key) in keys {
+            labels[id] = key;
+        }
+
+        let line_width = width * column.len();
+        for (level, ids) in levels.iter().enumerate() {
+            if level > 0 {
+                // the links to the parents, placed between the parent and the child columns
+                let mut links = vec![b' '; line_width + 1];
+                for &id in &levels[level - 1] {
+                    let node = self.get_node(Some(id)).unwrap();
+                    if let Some(left) = node.id_left {
+                        links[(column[left] + 1) * width - 1] = b'/';
+                    }
+                    if let Some(right) = node.id_right {
+                        links[column[right] * width] = b'\\';
+                    }
+                }
+                writeln!(f, "{}", String::from_utf8(links).unwrap().trim_end())?;
+            }
+
+            let mut line = String::new();
+            for &id in ids {
+                let start = column[id] * width;
+                line.push_str(&" ".repeat(start - line.chars().count()));
+                write!(line, "{:>w$}", labels[id], w = width - 1)?;
+            }
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+
+        assert_eq!(
+            tree.to_string(),
+            "      10\n  /         \\\n 5          22\n   \\       /\n    7    20\n"
+        );
+
+        let tree = Tree::with_root(3);
+        assert_eq!(tree.to_string(), "3\n");
+
+        let mut tree = Tree::with_root(1);
+        tree.detach_subtree(Tree::ROOT_NODE).unwrap();
+        assert_eq!(tree.to_string(), "");
+
+        // columns are measured in chars, not in bytes
+        let tree: Tree<String> = Tree::parse_level_order("[é,ab,ñ]").unwrap();
+        assert_eq!(tree.to_string(), "    é\n  /   \\\nab     ñ\n");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7]").unwrap();
+
+        assert_eq!(
+            tree.to_dot(Highlight::Nothing),
+            "digraph tree {\n    \
+                0 [label=\"0: 10\"];\n    \
+                0 -> 1 [label=\"L\"];\n    \
+                0 -> 2 [label=\"R\"];\n    \
+                1 [label=\"1: 5\"];\n    \
+                1 -> 3 [label=\"R\"];\n    \
+                3 [label=\"3: 7\"];\n    \
+                2 [label=\"2: 22\"];\n\
+            }\n"
+        );
+
+        let tree: Tree<String> = Tree::with_root(r#"a"b\c"#.to_string());
+        assert_eq!(
+            tree.to_dot(Highlight::Nothing),
+            "digraph tree {\n    0 [label=\"0: a\\\"b\\\\c\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_dot_highlight() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,12,20]").unwrap();
+
+        let dot = tree.to_dot(Highlight::BstViolations);
+        assert_eq!(dot.matches("fillcolor=red").count(), 1);
+        assert!(dot.contains("0 [label=\"0: 10\", style=filled"));
+
+        // 15 is deep in the left subtree of 10, yet ordered with respect to 5 and 7
+        let deep: Tree<u32> =
+            Tree::parse_level_order("[10,5,22,null,7,null,null,null,15]").unwrap();
+        let dot = deep.to_dot(Highlight::BstViolations);
+        assert_eq!(dot.matches("fillcolor=red").count(), 1);
+        assert!(dot.contains("0 [label=\"0: 10\", style=filled"));
+        assert!(dot.contains("4 [label=\"4: 15\"];"));
+
+        let dot = tree.to_dot(Highlight::MaxHeapViolations);
+        assert_eq!(dot.matches("fillcolor=red").count(), 2);
+        assert!(dot.contains("2 [label=\"2: 22\"];"));
+    }
+}
@@ -0,0 +1,306 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * checks of `Tree` that explain where and why they fail
+ */
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::Tree;
+
+/// Where and why a check on a `Tree` failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The node breaking the property.
+    pub node_id: usize,
+    /// The ids from the root of the checked subtree down to `node_id`, both included.
+    pub path: Vec<usize>,
+    pub reason: Reason,
+}
+
+/// The property broken by the node of a [`Violation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The key of the node is not smaller (if `is_left`) or not greater than the key of
+    /// `ancestor_id`, although the node is in its left (if `is_left`) or right subtree.
+    BstOrder { ancestor_id: usize, is_left: bool },
+    /// The heights of the left and right subtree of the node differ by more than one.
+    Unbalanced {
+        left_height: usize,
+        right_height: usize,
+    },
+    /// The key of the node is greater than the key of its parent, or not comparable to it.
+    HeapOrder { parent_id: usize },
+    /// The node is on `level` (the checked node is on level 0), but a position before
+    /// it in level-order is empty, so the tree is not complete.
+    Incomplete { level: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} ", self.node_id)?;
+        match self.reason {
+            Reason::BstOrder {
+                ancestor_id,
+                is_left,
+            } => write!(
+                f,
+                "is in the {} subtree of node {ancestor_id} but not {} than it",
+                if is_left { "left" } else { "right" },
+                if is_left { "smaller" } else { "greater" }
+            )?,
+            Reason::Unbalanced {
+                left_height,
+                right_height,
+            } => write!(
+                f,
+                "has subtrees of heights {left_height} and {right_height}"
+            )?,
+            Reason::HeapOrder { parent_id } => {
+                write!(f, "is not ordered below its parent {parent_id}")?
+            }
+            Reason::Incomplete { level } => {
+                write!(f, "follows an empty position on level {level}")?
+            }
+        }
+
+        let path: Vec<String> = self.path.iter().map(usize::to_string).collect();
+        write!(f, " (path {})", path.join(" -> "))
+    }
+}
+
+impl<K> Tree<K> {
+    /// Same as [`Tree::is_bst`], but reports the first node, in pre-order, whose key is
+    /// out of the range allowed by its ancestors.
+    pub fn check_bst(&self, node_id: usize) -> Result<(), Violation>
+    where
+        K: Ord,
+    {
+        // every node comes with the closest ancestors it must be greater and smaller than
+        let mut stack = vec![(self.subtree(node_id), None, None)];
+
+        while let Some((id, lower, upper)) = stack.pop() {
+            let Some(node) = self.get_node(id) else {
+                continue;
+            };
+            let id = id.unwrap();
+
+            let reason = match (lower, upper) {
+                (Some(lower), _) if node.key <= *self.get(lower) => Some(Reason::BstOrder {
+                    ancestor_id: lower,
+                    is_left: false,
+                }),
+                (_, Some(upper)) if node.key >= *self.get(upper) => Some(Reason::BstOrder {
+                    ancestor_id: upper,
+                    is_left: true,
+                }),
+                _ => None,
+            };
+            if let Some(reason) = reason {
+                return Err(self.violation(node_id, id, reason));
+            }
+
+            stack.push((node.id_right, Some(id), upper));
+            stack.push((node.id_left, lower, Some(id)));
+        }
+
+        Ok(())
+    }
+
+    /// Same as [`Tree::is_balanced`], but reports the first unbalanced node in post-order,
+    /// i.e. one whose subtrees are balanced.
+    pub fn check_balanced(&self, node_id: usize) -> Result<(), Violation> {
+        let mut heights = vec![0; self.nodes.len()];
+        let height = |heights: &[usize], id: Option<usize>| id.map_or(0, |id| heights[id]);
+
+        for (id, _) in self.post_order(node_id) {
+            let node = self.get_node(Some(id)).unwrap();
+            let left_height = height(&heights, node.id_left);
+            let right_height = height(&heights, node.id_right);
+
+            if left_height.abs_diff(right_height) > 1 {
+                let reason = Reason::Unbalanced {
+                    left_height,
+                    right_height,
+                };
+                return Err(self.violation(node_id, id, reason));
+            }
+            heights[id] = left_height.max(right_height) + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Same as [`Tree::is_max_heap`], but reports the first node, in level-order, that is
+    /// greater than its parent, or not comparable to it, or that follows an empty position.
+    pub fn check_max_heap(&self, node_id: usize) -> Result<(), Violation>
+    where
+        K: PartialOrd,
+    {
+        let mut queue = VecDeque::from([(self.subtree(node_id), 0)]);
+        let mut gap = false;
+
+        while let Some((id, level)) = queue.pop_front() {
+            let Some(node) = self.get_node(id) else {
+                gap = true;
+                continue;
+            };
+            let id = id.unwrap();
+
+            // the parent of the checked node is not part of the check
+            let parent = node.id_parent.filter(|_| id != node_id);
+            let reason = match parent {
+                _ if gap => Some(Reason::Incomplete { level }),
+                // the same test as `is_max_heap`, which fails for keys that are not comparable
+                Some(parent_id)
+                    if node
+                        .key
+                        .partial_cmp(self.get(parent_id))
+                        .is_none_or(Ordering::is_gt) =>
+                {
+                    Some(Reason::HeapOrder { parent_id })
+                }
+                _ => None,
+            };
+            if let Some(reason) = reason {
+                return Err(self.violation(node_id, id, reason));
+            }
+
+            queue.push_back((node.id_left, level + 1));
+            queue.push_back((node.id_right, level + 1));
+        }
+
+        Ok(())
+    }
+
+    // Builds the report for `node_id`, found while checking the subtree rooted at `top`
+    fn violation(&self, top: usize, node_id: usize, reason: Reason) -> Violation {
+        let mut path = vec![node_id];
+        while *path.last().unwrap() != top {
+            path.push(self.parent(*path.last().unwrap()).unwrap());
+        }
+        path.reverse();
+
+        Violation {
+            node_id,
+            path,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_bst() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        assert_eq!(tree.check_bst(Tree::ROOT_NODE), Ok(()));
+
+        // 12 is greater than its parent 7, but sits in the left subtree of 10
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20,null,null,12]").unwrap();
+        let violation = tree.check_bst(Tree::ROOT_NODE).unwrap_err();
+        assert_eq!(
+            violation,
+            Violation {
+                node_id: 5,
+                path: vec![0, 1, 3, 5],
+                reason: Reason::BstOrder {
+                    ancestor_id: 0,
+                    is_left: true
+                }
+            }
+        );
+        assert_eq!(
+            violation.to_string(),
+            "node 5 is in the left subtree of node 0 but not smaller than it (path 0 -> 1 -> 3 -> 5)"
+        );
+        assert_eq!(tree.check_bst(1), Ok(()));
+
+        // duplicates break the ordering as well
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,10]").unwrap();
+        assert_eq!(
+            tree.check_bst(Tree::ROOT_NODE).unwrap_err().reason,
+            Reason::BstOrder {
+                ancestor_id: 0,
+                is_left: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_balanced() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,null,null,null,5]").unwrap();
+        let violation = tree.check_balanced(Tree::ROOT_NODE).unwrap_err();
+        assert_eq!(violation.node_id, 1);
+        assert_eq!(violation.path, vec![0, 1]);
+        assert_eq!(
+            violation.reason,
+            Reason::Unbalanced {
+                left_height: 2,
+                right_height: 0
+            }
+        );
+
+        assert_eq!(tree.check_balanced(3), Ok(()));
+    }
+
+    #[test]
+    fn test_check_max_heap() {
+        let tree: Tree<u32> = Tree::parse_level_order("[9,7,8,3,5,1]").unwrap();
+        assert_eq!(tree.check_max_heap(Tree::ROOT_NODE), Ok(()));
+
+        let tree: Tree<u32> = Tree::parse_level_order("[9,7,8,3,10,1]").unwrap();
+        let violation = tree.check_max_heap(Tree::ROOT_NODE).unwrap_err();
+        assert_eq!(violation.path, vec![0, 1, 4]);
+        assert_eq!(violation.reason, Reason::HeapOrder { parent_id: 1 });
+
+        let tree: Tree<u32> = Tree::parse_level_order("[9,7,8,3,null,1]").unwrap();
+        let violation = tree.check_max_heap(Tree::ROOT_NODE).unwrap_err();
+        assert_eq!(violation.node_id, 4);
+        assert_eq!(violation.reason, Reason::Incomplete { level: 2 });
+        assert_eq!(
+            violation.to_string(),
+            "node 4 follows an empty position on level 2 (path 0 -> 2 -> 4)"
+        );
+    }
+
+    #[test]
+    fn test_checks_agree() {
+        for text in [
+            "[10,5,22,null,7,20]",
+            "[128,110,50,20,15,15,15,2]",
+            "[1,2,null,3]",
+            "[5,3,8,1,4,7,9]",
+        ] {
+            let tree: Tree<u32> = Tree::parse_level_order(text).unwrap();
+            for (id, _) in tree.pre_order(Tree::ROOT_NODE) {
+                assert_eq!(tree.check_bst(id).is_ok(), tree.is_bst(id));
+                assert_eq!(tree.check_balanced(id).is_ok(), tree.is_balanced(id));
+                assert_eq!(tree.check_max_heap(id).is_ok(), tree.is_max_heap(id));
+            }
+        }
+
+        // NaN is not comparable to any key, so it never sits below its parent
+        for text in ["[1.0,NaN]", "[NaN,1.0]", "[2.5,1.5,0.5]"] {
+            let tree: Tree<f64> = Tree::parse_level_order(text).unwrap();
+            for (id, _) in tree.pre_order(Tree::ROOT_NODE) {
+                assert_eq!(
+                    tree.check_max_heap(id).is_ok(),
+                    tree.is_max_heap(id),
+                    "{text}"
+                );
+            }
+        }
+        let tree: Tree<f64> = Tree::parse_level_order("[1.0,NaN]").unwrap();
+        assert_eq!(
+            tree.check_max_heap(Tree::ROOT_NODE).unwrap_err().reason,
+            Reason::HeapOrder { parent_id: 0 }
+        );
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * predicates on the shape of a subtree of `Tree`, regardless of its keys
+ */
+
+use crate::Tree;
+
+// The shape of a subtree, as much as needed to tell whether it is complete or perfect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Shape {
+    pub(crate) height: usize,
+    pub(crate) is_complete: bool,
+    pub(crate) is_perfect: bool,
+}
+
+impl Shape {
+    // The shape of an empty subtree
+    pub(crate) const EMPTY: Shape = Shape {
+        height: 0,
+        is_complete: true,
+        is_perfect: true,
+    };
+
+    // The shape of a node whose subtrees have the shapes `left` and `right`.
+    // The last level either ends in the right subtree, so the left one is perfect and
+    // as high as the right one, or it ends in the left subtree, so the right one is
+    // perfect and one level lower.
+    pub(crate) fn join(left: Shape, right: Shape) -> Shape {
+        let is_complete = (left.is_perfect && right.is_complete && left.height == right.height)
+            || (left.is_complete && right.is_perfect && left.height == right.height + 1);
+
+        Shape {
+            height: left.height.max(right.height) + 1,
+            is_complete,
+            is_perfect: left.is_perfect && right.is_perfect && left.height == right.height,
+        }
+    }
+}
+
+impl<K> Tree<K> {
+    /// Returns `true` if the subtree rooted at `node_id` is complete: every level but
+    /// the last is full, and the nodes of the last level are as far left as possible.
+    pub fn is_complete(&self, node_id: usize) -> bool {
+        self.shape(node_id).is_complete
+    }
+
+    /// Returns `true` if every node of the subtree rooted at `node_id` has either
+    /// no children or both of them.
+    pub fn is_full(&self, node_id: usize) -> bool {
+        self.pre_order(node_id).all(|(id, _)| {
+            let node = self.get_node(Some(id)).unwrap();
+            node.id_left.is_some() == node.id_right.is_some()
+        })
+    }
+
+    /// Returns `true` if all the levels of the subtree rooted at `node_id` are full,
+    /// i.e. it has exactly `2^height - 1` nodes.
+    pub fn is_perfect(&self, node_id: usize) -> bool {
+        self.shape(node_id).is_perfect
+    }
+
+    fn shape(&self, node_id: usize) -> Shape {
+        self.evaluate(
+            self.subtree(node_id),
+            || Shape::EMPTY,
+            |_, left, right| Shape::join(left, right),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes() {
+        // (level-order, complete, full, perfect)
+        for (text, complete, full, perfect) in [
+            ("[1]", true, true, true),
+            ("[1,2]", true, false, false),
+            ("[1,null,2]", false, false, false),
+            ("[1,2,3]", true, true, true),
+            ("[1,2,3,4,5]", true, true, false),
+            ("[1,2,3,4]", true, false, false),
+            ("[1,2,3,null,null,4,5]", false, true, false),
+            ("[1,2,3,4,5,6,7]", true, true, true),
+            ("[1,2,3,4,null,5]", false, false, false),
+        ] {
+            let tree: Tree<u32> = Tree::parse_level_order(text).unwrap();
+            assert_eq!(tree.is_complete(Tree::ROOT_NODE), complete, "{text}");
+            assert_eq!(tree.is_full(Tree::ROOT_NODE), full, "{text}");
+            assert_eq!(tree.is_perfect(Tree::ROOT_NODE), perfect, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_shapes_of_subtrees() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,null,null,4,5]").unwrap();
+        assert!(tree.is_perfect(2));
+        assert!(tree.is_complete(1));
+
+        let mut tree = Tree::with_root(1);
+        tree.remove_leaf(Tree::ROOT_NODE).unwrap();
+        assert!(tree.is_complete(Tree::ROOT_NODE));
+        assert!(tree.is_full(Tree::ROOT_NODE));
+        assert!(tree.is_perfect(Tree::ROOT_NODE));
+    }
+
+    #[test]
+    fn test_unordered_heap_is_complete() {
+        for text in [
+            "[9,7,8,3,5,1]",
+            "[9,7,8,3,null,1]",
+            "[0,0,0,null,null,0]",
+            "[0,0,0,0]",
+        ] {
+            let tree: Tree<u32> = Tree::parse_level_order(text).unwrap();
+            // all the keys are ordered, so only the shape decides
+            let ordered = tree.is_heap_by(Tree::ROOT_NODE, |_, _| true);
+            assert_eq!(ordered, tree.is_complete(Tree::ROOT_NODE), "{text}");
+        }
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * structural comparison of trees, regardless of the ids of their nodes
+ */
+
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::Tree;
+
+// Numbers the distinct shapes met so far: two subtrees get the same class if and only
+// if their roots have equal keys and their children have the same classes
+type Classes<'a, K> = HashMap<(&'a K, Option<usize>, Option<usize>), usize>;
+
+impl<K> Tree<K> {
+    /// Returns `true` if the subtree rooted at `node_id` and the subtree of `other` rooted
+    /// at `other_id` have the same shape and the same keys in the same places.
+    /// The ids of the nodes play no role.
+    pub fn subtree_eq(&self, node_id: usize, other: &Tree<K>, other_id: usize) -> bool
+    where
+        K: PartialEq,
+    {
+        let mut stack = vec![(self.subtree(node_id), other.subtree(other_id))];
+
+        while let Some((id, other_id)) = stack.pop() {
+            match (self.get_node(id), other.get_node(other_id)) {
+                (None, None) => {}
+                (Some(node), Some(other_node)) if node.key == other_node.key => {
+                    stack.push((node.id_left, other_node.id_left));
+                    stack.push((node.id_right, other_node.id_right));
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns a hash of the subtree rooted at `node_id` that depends only on its shape
+    /// and keys, like in a Merkle tree: the hash of a node combines its key with the
+    /// hashes of its children. Equal subtrees always have the same hash.
+    pub fn subtree_hash(&self, node_id: usize) -> u64
+    where
+        K: Hash,
+    {
+        self.evaluate(
+            self.subtree(node_id),
+            || 0,
+            |node, left: u64, right: u64| {
+                let mut hasher = DefaultHasher::new();
+                node.key.hash(&mut hasher);
+                left.hash(&mut hasher);
+                right.hash(&mut hasher);
+                // keeps the hash of a node apart from the hash of an empty subtree
+                hasher.finish() | 1
+            },
+        )
+    }
+
+    /// Returns `true` if the subtree rooted at `node_id` can be turned into the subtree
+    /// of `other` rooted at `other_id` by swapping the children of some of its nodes.
+    pub fn is_isomorphic(&self, node_id: usize, other: &Tree<K>, other_id: usize) -> bool
+    where
+        K: Eq + Hash,
+    {
+        let mut classes = HashMap::new();
+        // an empty tree has no class
+        let class = self
+            .classes(node_id, &mut classes, true)
+            .get(node_id)
+            .copied();
+        let other_class = other
+            .classes(other_id, &mut classes, true)
+            .get(other_id)
+            .copied();
+
+        class == other_class
+    }
+
+    /// Lists the groups of nodes whose subtrees are equal, ignoring ids.
+    /// Every group has at least two nodes; the groups and their nodes are listed
+    /// in the order in which a post-order traversal completes them.
+    pub fn duplicate_subtrees(&self) -> Vec<Vec<usize>>
+    where
+        K: Eq + Hash,
+    {
+        let mut classes = HashMap::new();
+        let class_of = self.classes(Tree::ROOT_NODE, &mut classes, false);
+
+        let mut groups: Vec<Vec<usize>> = vec![Vec::new(); classes.len()];
+        for (id, _) in self.post_order(Tree::ROOT_NODE) {
+            groups[class_of[id].unwrap()].push(id);
+        }
+        groups.retain(|group| group.len() > 1);
+
+        groups
+    }
+
+    // Assigns a class from `classes` to every node of the subtree rooted at `node_id`.
+    // With `unordered`, the classes of the two children are sorted, so that swapping
+    // the children does not change the class of the node.
+    fn classes<'a>(
+        &'a self,
+        node_id: usize,
+        classes: &mut Classes<'a, K>,
+        unordered: bool,
+    ) -> Vec<Option<usize>>
+    where
+        K: Eq + Hash,
+    {
+        let mut class_of = vec![None; self.nodes.len()];
+
+        for (id, key) in self.post_order(node_id) {
+            let node = self.get_node(Some(id)).unwrap();
+            let mut left = node.id_left.and_then(|child| class_of[child]);
+            let mut right = node.id_right.and_then(|child| class_of[child]);
+            if unordered && left > right {
+                std::mem::swap(&mut left, &mut right);
+            }
+
+            let next_class = classes.len();
+            class_of[id] = Some(match classes.entry((key, left, right)) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => *entry.insert(next_class),
+            });
+        }
+
+        class_of
+    }
+}
+
+/// Two trees are equal if they have the same shape and the same keys in the same
+/// places. The ids of the nodes, and the vacant slots left by removals, play no role.
+impl<K: PartialEq> PartialEq for Tree<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.subtree_eq(Tree::ROOT_NODE, other, Tree::ROOT_NODE)
+    }
+}
+
+impl<K: Eq> Eq for Tree<K> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality_ignores_ids() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+
+        // the same tree, built in a different order
+        let mut other = Tree::with_root(10);
+        let right = other.add_node(Tree::ROOT_NODE, 22, false);
+        other.add_node(right, 20, true);
+        let left = other.add_node(Tree::ROOT_NODE, 5, true);
+        other.add_node(left, 7, false);
+
+        assert_eq!(tree, other);
+        assert_eq!(
+            tree.subtree_hash(Tree::ROOT_NODE),
+            other.subtree_hash(Tree::ROOT_NODE)
+        );
+
+        other.remove_leaf(4).unwrap();
+        assert_ne!(tree, other);
+        other.add_node(left, 7, false);
+        assert_eq!(tree, other);
+
+        assert!(tree.subtree_eq(1, &other, left));
+        assert!(!tree.subtree_eq(1, &other, right));
+        assert_ne!(
+            tree,
+            Tree::parse_level_order("[10,5,22,7,null,20]").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_subtree_hash() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,2,3,null,3,null]").unwrap();
+
+        assert_eq!(tree.subtree_hash(1), tree.subtree_hash(2));
+        assert_ne!(tree.subtree_hash(1), tree.subtree_hash(3));
+        assert_ne!(tree.subtree_hash(Tree::ROOT_NODE), tree.subtree_hash(1));
+
+        // a child on the other side changes the hash
+        let mirrored: Tree<u32> = Tree::parse_level_order("[2,null,3]").unwrap();
+        assert_ne!(mirrored.subtree_hash(Tree::ROOT_NODE), tree.subtree_hash(1));
+    }
+
+    #[test]
+    fn test_is_isomorphic() {
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,5,null,6]").unwrap();
+        let flipped: Tree<u32> = Tree::parse_level_order("[1,3,2,6,null,5,4]").unwrap();
+
+        assert_ne!(tree, flipped);
+        assert!(tree.is_isomorphic(Tree::ROOT_NODE, &flipped, Tree::ROOT_NODE));
+
+        let different: Tree<u32> = Tree::parse_level_order("[1,3,2,6,null,5,7]").unwrap();
+        assert!(!tree.is_isomorphic(Tree::ROOT_NODE, &different, Tree::ROOT_NODE));
+        assert!(tree.is_isomorphic(1, &flipped, 2));
+    }
+
+    #[test]
+    fn test_duplicate_subtrees() {
+        //        1
+        //     2     3
+        //   4     2   4
+        //        4
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,null,2,4,null,null,4]").unwrap();
+
+        assert_eq!(tree.duplicate_subtrees(), vec![vec![3, 6, 5], vec![1, 4]]);
+
+        let tree: Tree<u32> = Tree::parse_level_order("[1,2,3]").unwrap();
+        assert_eq!(tree.duplicate_subtrees(), Vec::<Vec<usize>>::new());
+    }
+}
@@ -0,0 +1,332 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * a `Tree` that caches the result of the checks for every subtree
+ */
+
+use crate::shape::Shape;
+use crate::{Tree, TreeError};
+
+/// A `Tree` that keeps a summary of every subtree up to date while it grows, so that
+/// `is_bst`, `is_balanced`, `is_max_heap` and the aggregates they rely on answer in
+/// O(1) for any node instead of walking the subtree.
+///
+/// Every mutation refreshes the summaries of the changed node and of its ancestors,
+/// so it costs O(depth) on top of the plain `Tree` operation.
+#[derive(Debug, Clone)]
+pub struct Summarized<K> {
+    tree: Tree<K>,
+    // indexed by node id, `None` for vacant slots
+    summaries: Vec<Option<Summary>>,
+}
+
+// What is known about the subtree rooted at a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Summary {
+    // ids of the nodes holding the smallest and the largest key of the subtree
+    min_id: usize,
+    max_id: usize,
+    size: usize,
+    is_bst: bool,
+    is_balanced: bool,
+    is_max_heap: bool,
+    shape: Shape,
+}
+
+impl<K: Ord> Summarized<K> {
+    /// Creates a tree with a single node, with id `Tree::ROOT_NODE`.
+    pub fn with_root(key: K) -> Self {
+        Self::from_tree(Tree::with_root(key))
+    }
+
+    /// Wraps an existing tree, summarizing all of its subtrees in O(n).
+    pub fn from_tree(tree: Tree<K>) -> Self {
+        let mut summarized = Self {
+            summaries: vec![None; tree.nodes.len()],
+            tree,
+        };
+        let ids: Vec<usize> = summarized
+            .tree
+            .post_order(Tree::ROOT_NODE)
+            .map(|(id, _)| id)
+            .collect();
+        for id in ids {
+            summarized.summaries[id] = Some(summarized.summarize(id));
+        }
+
+        summarized
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &Tree<K> {
+        &self.tree
+    }
+
+    /// Unwraps the underlying tree, dropping the summaries.
+    pub fn into_tree(self) -> Tree<K> {
+        self.tree
+    }
+
+    /// Returns the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if the tree has no node, e.g. because it was built from empty
+    /// input or its root has been removed.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Same as [`Tree::add_node`], refreshing the summaries of the new node's ancestors.
+    pub fn add_node(&mut self, parent_id: usize, key: K, is_left: bool) -> usize {
+        match self.try_add_node(parent_id, key, is_left) {
+            Ok(child_id) => child_id,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Same as [`Tree::try_add_node`], refreshing the summaries of the new node's ancestors.
+    pub fn try_add_node(
+        &mut self,
+        parent_id: usize,
+        key: K,
+        is_left: bool,
+    ) -> Result<usize, TreeError> {
+        let node_id = self.tree.try_add_node(parent_id, key, is_left)?;
+        self.summaries.resize(self.tree.nodes.len(), None);
+        self.refresh(Some(node_id));
+
+        Ok(node_id)
+    }
+
+    /// Same as [`Tree::remove_leaf`], refreshing the summaries of the leaf's ancestors.
+    pub fn remove_leaf(&mut self, node_id: usize) -> Result<K, TreeError> {
+        let parent_id = self.tree.try_node(node_id)?.id_parent;
+        let key = self.tree.remove_leaf(node_id)?;
+        self.summaries.truncate(self.tree.nodes.len());
+        if let Some(summary) = self.summaries.get_mut(node_id) {
+            *summary = None;
+        }
+        self.refresh(parent_id);
+
+        Ok(key)
+    }
+
+    /// Same as [`Tree::is_bst`], in O(1).
+    pub fn is_bst(&self, node_id: usize) -> bool {
+        self.summary(node_id).is_none_or(|summary| summary.is_bst)
+    }
+
+    /// Same as [`Tree::is_balanced`], in O(1).
+    pub fn is_balanced(&self, node_id: usize) -> bool {
+        self.summary(node_id)
+            .is_none_or(|summary| summary.is_balanced)
+    }
+
+    /// Same as [`Tree::is_max_heap`], in O(1).
+    pub fn is_max_heap(&self, node_id: usize) -> bool {
+        self.summary(node_id)
+            .is_none_or(|summary| summary.is_max_heap)
+    }
+
+    /// Returns `true` if the subtree rooted at `node_id` is complete: every level but
+    /// the last is full, and the nodes of the last level are as far left as possible.
+    pub fn is_complete(&self, node_id: usize) -> bool {
+        self.summary(node_id)
+            .is_none_or(|summary| summary.shape.is_complete)
+    }
+
+    /// Same as [`Tree::height`], in O(1).
+    pub fn height(&self, node_id: usize) -> usize {
+        self.summary(node_id)
+            .map_or(0, |summary| summary.shape.height)
+    }
+
+    /// Same as [`Tree::size`], in O(1).
+    pub fn size(&self, node_id: usize) -> usize {
+        self.summary(node_id).map_or(0, |summary| summary.size)
+    }
+
+    /// Same as [`Tree::min`], in O(1).
+    pub fn min(&self, node_id: usize) -> Option<&K> {
+        self.summary(node_id)
+            .map(|summary| self.tree.get(summary.min_id))
+    }
+
+    /// Same as [`Tree::max`], in O(1).
+    pub fn max(&self, node_id: usize) -> Option<&K> {
+        self.summary(node_id)
+            .map(|summary| self.tree.get(summary.max_id))
+    }
+
+    // Returns the summary of the subtree rooted at `node_id`, `None` if it is empty.
+    // Panics if `node_id` is not in the tree, like `Tree::get`.
+    fn summary(&self, node_id: usize) -> Option<&Summary> {
+        self.tree.get_node(self.tree.subtree(node_id))?;
+        self.summaries[node_id].as_ref()
+    }
+
+    // Recomputes the summaries of `node_id` and of all of its ancestors
+    fn refresh(&mut self, mut node_id: Option<usize>) {
+        while let Some(id) = node_id {
+            self.summaries[id] = Some(self.summarize(id));
+            node_id = self.tree.get_node(Some(id)).unwrap().id_parent;
+        }
+    }
+
+    // Computes the summary of `node_id` from the summaries of its children
+    fn summarize(&self, node_id: usize) -> Summary {
+        let node = self.tree.get_node(Some(node_id)).unwrap();
+        let left = node.id_left.map(|id| self.summaries[id].unwrap());
+        let right = node.id_right.map(|id| self.summaries[id].unwrap());
+        let key = |id: usize| self.tree.get(id);
+
+        let min_id = [left.map(|s| s.min_id), right.map(|s| s.min_id)]
+            .into_iter()
+            .flatten()
+            .fold(
+                node_id,
+                |min_id, id| if key(id) < key(min_id) { id } else { min_id },
+            );
+        let max_id = [left.map(|s| s.max_id), right.map(|s| s.max_id)]
+            .into_iter()
+            .flatten()
+            .fold(
+                node_id,
+                |max_id, id| if key(id) > key(max_id) { id } else { max_id },
+            );
+
+        // an empty subtree is a BST, balanced, a perfect max-heap of height 0
+        let shape_of = |summary: Option<Summary>| summary.map_or(Shape::EMPTY, |s| s.shape);
+        let holds = |summary: Option<Summary>, property: fn(&Summary) -> bool| {
+            summary.as_ref().is_none_or(property)
+        };
+        let (left_shape, right_shape) = (shape_of(left), shape_of(right));
+
+        let is_bst = holds(left, |s| s.is_bst)
+            && holds(right, |s| s.is_bst)
+            && left.is_none_or(|s| *key(s.max_id) < node.key)
+            && right.is_none_or(|s| node.key < *key(s.min_id));
+        let is_balanced = holds(left, |s| s.is_balanced)
+            && holds(right, |s| s.is_balanced)
+            && left_shape.height.abs_diff(right_shape.height) <= 1;
+
+        let shape = Shape::join(left_shape, right_shape);
+        let is_max_heap = shape.is_complete
+            && holds(left, |s| s.is_max_heap)
+            && holds(right, |s| s.is_max_heap)
+            && [node.id_left, node.id_right]
+                .into_iter()
+                .flatten()
+                .all(|child| *key(child) <= node.key);
+
+        Summary {
+            min_id,
+            max_id,
+            size: left.map_or(0, |s| s.size) + right.map_or(0, |s| s.size) + 1,
+            is_bst,
+            is_balanced,
+            is_max_heap,
+            shape,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    // Checks every cached answer against the walking implementation of `Tree`
+    fn assert_summaries(summarized: &Summarized<u32>) {
+        let tree = summarized.tree();
+        for (id, _) in tree.pre_order(Tree::ROOT_NODE) {
+            assert_eq!(summarized.is_bst(id), tree.is_bst(id));
+            assert_eq!(summarized.is_balanced(id), tree.is_balanced(id));
+            assert_eq!(summarized.is_max_heap(id), tree.is_max_heap(id));
+            assert_eq!(summarized.height(id), tree.height(id));
+            assert_eq!(summarized.size(id), tree.size(id));
+            assert_eq!(summarized.min(id), tree.min(id));
+            assert_eq!(summarized.max(id), tree.max(id));
+        }
+    }
+
+    #[test]
+    fn test_add_node() {
+        let mut tree = Summarized::with_root(10);
+        assert!(tree.is_max_heap(Tree::ROOT_NODE));
+
+        tree.add_node(Tree::ROOT_NODE, 5, true); // id 1
+        tree.add_node(Tree::ROOT_NODE, 22, false); // id 2
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(!tree.is_max_heap(Tree::ROOT_NODE));
+        assert!(tree.is_complete(Tree::ROOT_NODE));
+
+        tree.add_node(1, 7, false); // id 3
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert!(!tree.is_complete(Tree::ROOT_NODE));
+        assert_eq!(tree.height(Tree::ROOT_NODE), 3);
+
+        tree.add_node(3, 12, false); // id 4
+        assert!(!tree.is_bst(Tree::ROOT_NODE));
+        assert!(tree.is_bst(1));
+        assert!(!tree.is_balanced(Tree::ROOT_NODE));
+        assert_eq!(tree.max(1), Some(&12));
+        assert_summaries(&tree);
+
+        assert_eq!(
+            tree.try_add_node(1, 3, false),
+            Err(TreeError::SlotOccupied {
+                parent_id: 1,
+                is_left: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_remove_leaf() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        let mut tree = Summarized::from_tree(tree);
+        assert_summaries(&tree);
+
+        assert_eq!(tree.remove_leaf(3), Ok(7));
+        assert!(!tree.is_complete(Tree::ROOT_NODE));
+        assert!(tree.is_balanced(Tree::ROOT_NODE));
+        assert_eq!(tree.size(Tree::ROOT_NODE), 4);
+        assert_summaries(&tree);
+
+        // the freed slot is reused by the next node
+        assert_eq!(tree.add_node(1, 30, false), 3);
+        assert!(!tree.is_bst(Tree::ROOT_NODE));
+        assert_summaries(&tree);
+
+        for id in [3, 4, 1, 2] {
+            tree.remove_leaf(id).unwrap();
+        }
+        assert_eq!(tree.remove_leaf(Tree::ROOT_NODE), Ok(10));
+        assert!(tree.is_empty());
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert_eq!(tree.height(Tree::ROOT_NODE), 0);
+        assert_eq!(tree.min(Tree::ROOT_NODE), None);
+    }
+
+    #[test]
+    fn test_random_growth() {
+        let mut rng = Rng::new(7);
+        let mut next = || rng.next() as usize;
+
+        for _ in 0..20 {
+            let mut tree = Summarized::with_root(next() as u32 % 100);
+            for _ in 0..60 {
+                let parent_id = next() % tree.len();
+                let key = next() as u32 % 100;
+                // a taken slot is rejected and leaves the summaries as they are
+                let _ = tree.try_add_node(parent_id, key, next() % 2 == 0);
+                assert_summaries(&tree);
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * in-place structural transforms of `Tree`: rotations, mirroring and swaps
+ */
+
+use crate::{Tree, TreeError};
+
+impl<K> Tree<K> {
+    /// Rotates the subtree rooted at `node_id` to the right, lifting its left child.
+    /// The in-order sequence of the keys is preserved, so a BST stays a BST.
+    ///
+    /// The keys move instead of the nodes, so `node_id` stays the root of the subtree and
+    /// the link from its parent is untouched: afterwards `node_id` holds the key of its
+    /// former left child. Returns the id of the node that moved down, which now holds
+    /// the key that was in `node_id`.
+    pub fn rotate_right(&mut self, node_id: usize) -> Result<usize, TreeError> {
+        //     y          x
+        //   x   c  =>  a   y
+        //  a b            b c
+        let pivot = self
+            .try_node(node_id)?
+            .id_left
+            .ok_or(TreeError::MissingChild {
+                node_id,
+                is_left: true,
+            })?;
+        let (a, b) = {
+            let pivot = self.get_node(Some(pivot)).unwrap();
+            (pivot.id_left, pivot.id_right)
+        };
+        let c = self.get_node(Some(node_id)).unwrap().id_right;
+
+        self.swap_keys(node_id, pivot);
+        self.set_child(node_id, true, a);
+        self.set_child(node_id, false, Some(pivot));
+        self.set_child(pivot, true, b);
+        self.set_child(pivot, false, c);
+
+        Ok(pivot)
+    }
+
+    /// The mirror image of [`Tree::rotate_right`], lifting the right child of `node_id`.
+    pub fn rotate_left(&mut self, node_id: usize) -> Result<usize, TreeError> {
+        //   y              x
+        //  a   x   =>    y   c
+        //     b c       a b
+        let pivot = self
+            .try_node(node_id)?
+            .id_right
+            .ok_or(TreeError::MissingChild {
+                node_id,
+                is_left: false,
+            })?;
+        let (b, c) = {
+            let pivot = self.get_node(Some(pivot)).unwrap();
+            (pivot.id_left, pivot.id_right)
+        };
+        let a = self.get_node(Some(node_id)).unwrap().id_left;
+
+        self.swap_keys(node_id, pivot);
+        self.set_child(node_id, true, Some(pivot));
+        self.set_child(node_id, false, c);
+        self.set_child(pivot, true, a);
+        self.set_child(pivot, false, b);
+
+        Ok(pivot)
+    }
+
+    /// Mirrors the subtree rooted at `node_id` by swapping the children of each of its
+    /// nodes. Mirroring the root of an empty tree does nothing.
+    pub fn mirror(&mut self, node_id: usize) -> Result<(), TreeError> {
+        if self.subtree(node_id).is_none() {
+            return Ok(());
+        }
+        self.try_node(node_id)?;
+
+        let ids: Vec<usize> = self.pre_order(node_id).map(|(id, _)| id).collect();
+        for id in ids {
+            let node = self.get_node_mut(id);
+            std::mem::swap(&mut node.id_left, &mut node.id_right);
+        }
+
+        Ok(())
+    }
+
+    /// Exchanges the places of the subtrees rooted at `a` and `b`: each one is attached
+    /// where the other was. Ids and keys stay with their nodes.
+    /// Fails with `TreeError::Overlapping` if one node is an ancestor of the other,
+    /// which includes the root; swapping a subtree with itself does nothing.
+    pub fn swap_subtrees(&mut self, a: usize, b: usize) -> Result<(), TreeError> {
+        self.try_node(a)?;
+        self.try_node(b)?;
+        if a == b {
+            return Ok(());
+        }
+        for (ancestor_id, node_id) in [(a, b), (b, a)] {
+            if self.path_to_root(node_id).contains(&ancestor_id) {
+                return Err(TreeError::Overlapping {
+                    ancestor_id,
+                    node_id,
+                });
+            }
+        }
+
+        // neither node is the root, as the root is an ancestor of every other node
+        let (parent_a, is_left_a) = self.parent_link(a).unwrap();
+        let (parent_b, is_left_b) = self.parent_link(b).unwrap();
+        self.set_child(parent_a, is_left_a, Some(b));
+        self.set_child(parent_b, is_left_b, Some(a));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    fn keys(tree: &Tree<u32>) -> Vec<u32> {
+        tree.level_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .collect()
+    }
+
+    #[test]
+    fn test_rotations() {
+        //        8
+        //     4     10
+        //   2   6
+        let mut tree: Tree<u32> = Tree::parse_level_order("[8,4,10,2,6]").unwrap();
+
+        assert_eq!(tree.rotate_right(Tree::ROOT_NODE), Ok(1));
+        tree.assert_links();
+        assert_eq!(keys(&tree), vec![4, 2, 8, 6, 10]);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+        assert_eq!(*tree.get(1), 8);
+
+        assert_eq!(tree.rotate_left(Tree::ROOT_NODE), Ok(1));
+        tree.assert_links();
+        assert_eq!(tree, Tree::parse_level_order("[8,4,10,2,6]").unwrap());
+
+        // rotations inside a subtree leave the rest of the tree alone
+        assert_eq!(tree.rotate_left(1), Ok(4));
+        tree.assert_links();
+        assert_eq!(keys(&tree), vec![8, 6, 10, 4, 2]);
+        assert!(tree.is_bst(Tree::ROOT_NODE));
+
+        assert_eq!(
+            tree.rotate_left(2),
+            Err(TreeError::MissingChild {
+                node_id: 2,
+                is_left: false
+            })
+        );
+        assert_eq!(tree.rotate_right(9), Err(TreeError::IdOutOfRange(9)));
+    }
+
+    #[test]
+    fn test_rotations_keep_bst() {
+        let mut rng = Rng::new(11);
+        let mut next = || rng.next() as usize;
+
+        let mut bst = crate::Bst::new();
+        for _ in 0..200 {
+            bst.insert(next() as u32 % 1000);
+        }
+        let mut tree = bst.into_tree();
+        let in_order: Vec<u32> = tree
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .collect();
+
+        for _ in 0..1000 {
+            let node_id = next() % tree.nodes.len();
+            let _ = if next() % 2 == 0 {
+                tree.rotate_left(node_id)
+            } else {
+                tree.rotate_right(node_id)
+            };
+            assert!(tree.is_bst(Tree::ROOT_NODE));
+        }
+        tree.assert_links();
+        let rotated: Vec<u32> = tree
+            .in_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .collect();
+        assert_eq!(rotated, in_order);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,null,null,5]").unwrap();
+
+        tree.mirror(2).unwrap();
+        assert_eq!(tree, Tree::parse_level_order("[1,2,3,4,null,5]").unwrap());
+
+        tree.mirror(Tree::ROOT_NODE).unwrap();
+        tree.assert_links();
+        assert_eq!(
+            tree,
+            Tree::parse_level_order("[1,3,2,null,5,null,4]").unwrap()
+        );
+
+        // mirroring twice gives back the same tree
+        let original = tree.clone();
+        tree.mirror(Tree::ROOT_NODE).unwrap();
+        tree.mirror(Tree::ROOT_NODE).unwrap();
+        assert_eq!(tree, original);
+
+        assert_eq!(tree.mirror(7), Err(TreeError::IdOutOfRange(7)));
+    }
+
+    #[test]
+    fn test_swap_subtrees() {
+        //        1
+        //     2     3
+        //   4   5     6
+        let mut tree: Tree<u32> = Tree::parse_level_order("[1,2,3,4,5,null,6]").unwrap();
+
+        tree.swap_subtrees(1, 5).unwrap();
+        tree.assert_links();
+        assert_eq!(
+            tree,
+            Tree::parse_level_order("[1,6,3,null,null,null,2,4,5]").unwrap()
+        );
+        assert_eq!(tree.parent(1), Some(2));
+
+        // siblings exchange their sides
+        tree.swap_subtrees(3, 4).unwrap();
+        tree.assert_links();
+        assert_eq!(keys(&tree), vec![1, 6, 3, 2, 5, 4]);
+
+        assert_eq!(tree.swap_subtrees(2, 2), Ok(()));
+        assert_eq!(
+            tree.swap_subtrees(3, 2),
+            Err(TreeError::Overlapping {
+                ancestor_id: 2,
+                node_id: 3
+            })
+        );
+        assert_eq!(
+            tree.swap_subtrees(Tree::ROOT_NODE, 4),
+            Err(TreeError::Overlapping {
+                ancestor_id: 0,
+                node_id: 4
+            })
+        );
+    }
+}
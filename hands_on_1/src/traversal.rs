@@ -0,0 +1,242 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * borrowing iterators over the nodes of a `Tree`
+ */
+
+use std::collections::VecDeque;
+
+use crate::Tree;
+
+/// Iterator over a subtree in in-order (left subtree, node, right subtree).
+/// Yields `(id, &key)` pairs. Created by [`Tree::in_order`].
+pub struct InOrder<'a, K> {
+    tree: &'a Tree<K>,
+    stack: Vec<usize>,
+    current: Option<usize>,
+}
+
+/// Iterator over a subtree in pre-order (node, left subtree, right subtree).
+/// Yields `(id, &key)` pairs. Created by [`Tree::pre_order`].
+pub struct PreOrder<'a, K> {
+    tree: &'a Tree<K>,
+    stack: Vec<usize>,
+}
+
+/// Iterator over a subtree in post-order (left subtree, right subtree, node).
+/// Yields `(id, &key)` pairs. Created by [`Tree::post_order`].
+pub struct PostOrder<'a, K> {
+    tree: &'a Tree<K>,
+    // every node is pushed twice: first to expand it, then to yield it
+    stack: Vec<(usize, bool)>,
+}
+
+/// Iterator over a subtree level by level, from left to right.
+/// Yields `(id, &key)` pairs. Created by [`Tree::level_order`].
+pub struct LevelOrder<'a, K> {
+    tree: &'a Tree<K>,
+    queue: VecDeque<usize>,
+}
+
+impl<K> Tree<K> {
+    /// Returns an iterator over the subtree rooted at `node_id` in in-order.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn in_order(&self, node_id: usize) -> InOrder<'_, K> {
+        let start = self.subtree(node_id);
+        self.get_node(start);
+        InOrder {
+            tree: self,
+            stack: Vec::new(),
+            current: start,
+        }
+    }
+
+    /// Returns an iterator over the subtree rooted at `node_id` in pre-order.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn pre_order(&self, node_id: usize) -> PreOrder<'_, K> {
+        let start = self.subtree(node_id);
+        self.get_node(start);
+        PreOrder {
+            tree: self,
+            stack: start.into_iter().collect(),
+        }
+    }
+
+    /// Returns an iterator over the subtree rooted at `node_id` in post-order.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn post_order(&self, node_id: usize) -> PostOrder<'_, K> {
+        let start = self.subtree(node_id);
+        self.get_node(start);
+        PostOrder {
+            tree: self,
+            stack: start.map(|id| (id, false)).into_iter().collect(),
+        }
+    }
+
+    /// Returns an iterator over the subtree rooted at `node_id` in level-order.
+    ///
+    /// # Panics
+    /// Panics if `node_id` does not exist.
+    pub fn level_order(&self, node_id: usize) -> LevelOrder<'_, K> {
+        let start = self.subtree(node_id);
+        self.get_node(start);
+        LevelOrder {
+            tree: self,
+            queue: start.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, K> Iterator for InOrder<'a, K> {
+    type Item = (usize, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // descend to the leftmost node not visited yet
+        while let Some(id) = self.current {
+            self.stack.push(id);
+            self.current = self.tree.get_node(Some(id)).unwrap().id_left;
+        }
+
+        let id = self.stack.pop()?;
+        let node = self.tree.get_node(Some(id)).unwrap();
+        self.current = node.id_right;
+
+        Some((id, &node.key))
+    }
+}
+
+impl<'a, K> Iterator for PreOrder<'a, K> {
+    type Item = (usize, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        let node = self.tree.get_node(Some(id)).unwrap();
+
+        // the right child is pushed first, so that the left one is visited first
+        self.stack.extend(node.id_right);
+        self.stack.extend(node.id_left);
+
+        Some((id, &node.key))
+    }
+}
+
+impl<'a, K> Iterator for PostOrder<'a, K> {
+    type Item = (usize, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, expanded)) = self.stack.pop() {
+            let node = self.tree.get_node(Some(id)).unwrap();
+            if expanded {
+                return Some((id, &node.key));
+            }
+
+            self.stack.push((id, true));
+            if let Some(right) = node.id_right {
+                self.stack.push((right, false));
+            }
+            if let Some(left) = node.id_left {
+                self.stack.push((left, false));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, K> Iterator for LevelOrder<'a, K> {
+    type Item = (usize, &'a K);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.queue.pop_front()?;
+        let node = self.tree.get_node(Some(id)).unwrap();
+
+        self.queue.extend(node.id_left);
+        self.queue.extend(node.id_right);
+
+        Some((id, &node.key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<'a>(iter: impl Iterator<Item = (usize, &'a u32)>) -> Vec<u32> {
+        iter.map(|(_, key)| *key).collect()
+    }
+
+    #[test]
+    fn test_in_order() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(
+            keys(tree.in_order(Tree::ROOT_NODE)),
+            vec![1, 5, 7, 10, 20, 22]
+        );
+        assert_eq!(keys(tree.in_order(2)), vec![20, 22]);
+        assert_eq!(
+            tree.in_order(1).map(|(id, _)| id).collect::<Vec<_>>(),
+            vec![3, 1, 4]
+        );
+    }
+
+    #[test]
+    fn test_pre_order() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(
+            keys(tree.pre_order(Tree::ROOT_NODE)),
+            vec![10, 5, 1, 7, 22, 20]
+        );
+        assert_eq!(keys(tree.pre_order(4)), vec![7]);
+    }
+
+    #[test]
+    fn test_post_order() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(
+            keys(tree.post_order(Tree::ROOT_NODE)),
+            vec![1, 7, 5, 20, 22, 10]
+        );
+        assert_eq!(keys(tree.post_order(2)), vec![20, 22]);
+    }
+
+    #[test]
+    fn test_level_order() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,1,7,20]").unwrap();
+
+        assert_eq!(
+            keys(tree.level_order(Tree::ROOT_NODE)),
+            vec![10, 5, 22, 1, 7, 20]
+        );
+        assert_eq!(keys(tree.level_order(1)), vec![5, 1, 7]);
+    }
+
+    #[test]
+    fn test_degenerate_traversals() {
+        let mut tree = Tree::with_root(0_u32);
+        let mut last = Tree::ROOT_NODE;
+        for key in 1..100_000 {
+            last = tree.add_node(last, key, true);
+        }
+
+        assert_eq!(tree.in_order(Tree::ROOT_NODE).count(), 100_000);
+        assert_eq!(
+            tree.post_order(Tree::ROOT_NODE).next(),
+            Some((last, &99_999))
+        );
+        assert!(tree
+            .pre_order(Tree::ROOT_NODE)
+            .map(|(_, key)| *key)
+            .eq(0..100_000));
+    }
+}
@@ -0,0 +1,324 @@
+/*
+ * Hands-on 1
+ * Competitive Programmming course @ UniPi
+ * Autumn 2023
+ *
+ * bottom-up evaluation of a subtree of `Tree` with a user-defined visitor
+ */
+
+use crate::shape::Shape;
+use crate::{Node, Tree};
+
+/// A bottom-up analysis of a subtree, evaluated by [`Tree::visit`].
+///
+/// `leaf` gives the result of an empty subtree, i.e. of a missing child, and `combine`
+/// merges the results of the left and right subtree of a node into the result of the
+/// node itself. The lifetime `'a` lets the results borrow the keys of the tree.
+pub trait Visitor<'a, K> {
+    type Output;
+
+    /// Returns the result of an empty subtree.
+    fn leaf(&mut self) -> Self::Output;
+
+    /// Returns the result of the subtree rooted at `node`, given as its id and its key,
+    /// from the results of its left and right subtree.
+    fn combine(
+        &mut self,
+        node: (usize, &'a K),
+        left: Self::Output,
+        right: Self::Output,
+    ) -> Self::Output;
+}
+
+impl<K> Tree<K> {
+    /// Evaluates `visitor` on the subtree rooted at `node_id` bottom-up, i.e. in
+    /// post-order, and returns the result of `node_id`. The root of an empty tree
+    /// is an empty subtree, whose result is `visitor.leaf()`.
+    ///
+    /// The traversal uses an explicit stack instead of recursion, so the depth of the
+    /// tree is only bounded by the available memory.
+    ///
+    /// # Panics
+    /// Panics if `node_id` is not in the tree.
+    pub fn visit<'a, V: Visitor<'a, K>>(&'a self, node_id: usize, visitor: &mut V) -> V::Output {
+        self.walk(self.subtree(node_id), visitor)
+    }
+
+    // The traversal behind `visit`, starting from a possibly empty subtree
+    pub(crate) fn walk<'a, V: Visitor<'a, K>>(
+        &'a self,
+        node_id: Option<usize>,
+        visitor: &mut V,
+    ) -> V::Output {
+        enum Step {
+            Enter(Option<usize>),
+            Exit(usize),
+        }
+
+        let mut stack = vec![Step::Enter(node_id)];
+        let mut results = Vec::new();
+
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(node_id) => match self.get_node(node_id) {
+                    Some(node) => {
+                        // the left subtree is entered first, so its result is pushed first
+                        stack.push(Step::Exit(node_id.unwrap()));
+                        stack.push(Step::Enter(node.id_right));
+                        stack.push(Step::Enter(node.id_left));
+                    }
+                    None => results.push(visitor.leaf()),
+                },
+                Step::Exit(id) => {
+                    let right = results.pop().unwrap();
+                    let left = results.pop().unwrap();
+                    let key = &self.get_node(Some(id)).unwrap().key;
+                    results.push(visitor.combine((id, key), left, right));
+                }
+            }
+        }
+
+        results.pop().unwrap()
+    }
+}
+
+// A visitor made of two closures, for the analyses written with `Tree::evaluate`.
+// `combine` receives the whole node rather than its id and key.
+pub(crate) struct Closures<'a, K, L, C> {
+    pub(crate) tree: &'a Tree<K>,
+    pub(crate) leaf: L,
+    pub(crate) combine: C,
+}
+
+impl<'a, K, R, L, C> Visitor<'a, K> for Closures<'a, K, L, C>
+where
+    L: Fn() -> R,
+    C: FnMut(&'a Node<K>, R, R) -> R,
+{
+    type Output = R;
+
+    fn leaf(&mut self) -> R {
+        (self.leaf)()
+    }
+
+    fn combine(&mut self, (node_id, _): (usize, &'a K), left: R, right: R) -> R {
+        (self.combine)(self.tree.get_node(Some(node_id)).unwrap(), left, right)
+    }
+}
+
+// The visitor behind `Tree::is_bst`
+pub(crate) struct BstCheck;
+
+impl<'a, K: Ord + 'a> Visitor<'a, K> for BstCheck {
+    // the result of every subtree:
+    //  - whether the processed node is BST
+    //  - maximum value in subtree
+    //  - minimum value in subtree
+    type Output = (bool, Option<&'a K>, Option<&'a K>);
+
+    fn leaf(&mut self) -> Self::Output {
+        (true, None, None)
+    }
+
+    fn combine(
+        &mut self,
+        (_, key): (usize, &'a K),
+        (left_is_bst, max_left, min_left): Self::Output,
+        (right_is_bst, max_right, min_right): Self::Output,
+    ) -> Self::Output {
+        // calculate minimum and maximum of node and its subtree
+        let node_max = match max_right {
+            Some(max_right) => max_right,
+            None => key,
+        };
+        let node_min = match min_left {
+            Some(min_left) => min_left,
+            None => key,
+        };
+
+        // chceck for BST property of current node
+        let left_satisfies_bst = match max_left {
+            Some(max_left) => max_left < key,
+            None => true,
+        };
+        let right_satisfies_bst = match min_right {
+            Some(min_right) => key < min_right,
+            None => true,
+        };
+        let node_is_bst = left_satisfies_bst && right_satisfies_bst;
+
+        (
+            left_is_bst && right_is_bst && node_is_bst,
+            Some(node_max),
+            Some(node_min),
+        )
+    }
+}
+
+// The visitor behind `Tree::is_balanced`
+pub(crate) struct BalanceCheck;
+
+impl<K> Visitor<'_, K> for BalanceCheck {
+    // the result of every subtree:
+    //  - whether the processed subtree is balanced
+    //  - maximum distance to leaf (height)
+    type Output = (bool, usize);
+
+    fn leaf(&mut self) -> Self::Output {
+        (true, 0)
+    }
+
+    fn combine(
+        &mut self,
+        _: (usize, &K),
+        (left_is_balanced, max_height_left): Self::Output,
+        (right_is_balanced, max_height_right): Self::Output,
+    ) -> Self::Output {
+        let node_is_balanced = max_height_left.abs_diff(max_height_right) <= 1;
+        let node_max_height = max_height_left.max(max_height_right) + 1;
+
+        (
+            left_is_balanced && right_is_balanced && node_is_balanced,
+            node_max_height,
+        )
+    }
+}
+
+// The visitor behind `Tree::is_heap_by`, holding the `precedes` relation
+pub(crate) struct HeapCheck<F>(pub(crate) F);
+
+impl<'a, K: 'a, F: FnMut(&K, &K) -> bool> Visitor<'a, K> for HeapCheck<F> {
+    // the result of every subtree:
+    //  - whether the processed node satisfies the heap property
+    //  - value of the processed node
+    //  - shape of the tree, to check for completeness
+    type Output = (bool, Option<&'a K>, Shape);
+
+    fn leaf(&mut self) -> Self::Output {
+        (true, None, Shape::EMPTY)
+    }
+
+    fn combine(
+        &mut self,
+        (_, key): (usize, &'a K),
+        (left_is_heap, left_value, left_shape): Self::Output,
+        (right_is_heap, right_value, right_shape): Self::Output,
+    ) -> Self::Output {
+        let precedes = &mut self.0;
+
+        // check for heap property
+        let left_heap = match left_value {
+            Some(left_value) => precedes(key, left_value),
+            None => true,
+        };
+        let right_heap = match right_value {
+            Some(right_value) => precedes(key, right_value),
+            None => true,
+        };
+
+        // check for completeness property
+        let shape = Shape::join(left_shape, right_shape);
+
+        let node_is_heap = left_heap && right_heap && shape.is_complete;
+
+        (
+            left_is_heap && right_is_heap && node_is_heap,
+            Some(key),
+            shape,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts the leaves, i.e. the nodes whose two subtrees are empty
+    struct Leaves;
+
+    impl<K> Visitor<'_, K> for Leaves {
+        // number of leaves, and whether the subtree is empty
+        type Output = (usize, bool);
+
+        fn leaf(&mut self) -> Self::Output {
+            (0, true)
+        }
+
+        fn combine(
+            &mut self,
+            _: (usize, &K),
+            (left, left_is_empty): Self::Output,
+            (right, right_is_empty): Self::Output,
+        ) -> Self::Output {
+            (
+                left + right + usize::from(left_is_empty && right_is_empty),
+                false,
+            )
+        }
+    }
+
+    // Records the ids in the order they are combined and returns the longest key
+    struct Longest {
+        order: Vec<usize>,
+    }
+
+    impl<'a> Visitor<'a, String> for Longest {
+        type Output = Option<&'a String>;
+
+        fn leaf(&mut self) -> Self::Output {
+            None
+        }
+
+        fn combine(
+            &mut self,
+            (node_id, key): (usize, &'a String),
+            left: Self::Output,
+            right: Self::Output,
+        ) -> Self::Output {
+            self.order.push(node_id);
+            [left, right]
+                .into_iter()
+                .flatten()
+                .fold(key, |longest, key| {
+                    if key.len() > longest.len() {
+                        key
+                    } else {
+                        longest
+                    }
+                })
+                .into()
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let tree: Tree<u32> = Tree::parse_level_order("[10,5,22,null,7,20]").unwrap();
+        assert_eq!(tree.visit(Tree::ROOT_NODE, &mut Leaves).0, 2);
+        assert_eq!(tree.visit(1, &mut Leaves).0, 1);
+
+        let tree: Tree<String> = Tree::parse_level_order("[a,bbb,cc,null,dddd]").unwrap();
+        let mut longest = Longest { order: Vec::new() };
+        assert_eq!(tree.visit(Tree::ROOT_NODE, &mut longest).unwrap(), "dddd");
+        let post_order: Vec<usize> = tree.post_order(Tree::ROOT_NODE).map(|(id, _)| id).collect();
+        assert_eq!(longest.order, post_order);
+
+        let mut tree = Tree::with_root(0);
+        tree.remove_leaf(Tree::ROOT_NODE).unwrap();
+        assert_eq!(tree.visit(Tree::ROOT_NODE, &mut Leaves), (0, true));
+    }
+
+    #[test]
+    fn test_visit_degenerate_tree() {
+        let mut tree = Tree::with_root(0);
+        let mut last = Tree::ROOT_NODE;
+        for key in 1..1_000_000 {
+            last = tree.add_node(last, key, key % 2 == 0);
+        }
+
+        assert_eq!(tree.visit(Tree::ROOT_NODE, &mut Leaves).0, 1);
+        assert_eq!(
+            tree.visit(Tree::ROOT_NODE, &mut BalanceCheck),
+            (false, 1_000_000)
+        );
+    }
+}